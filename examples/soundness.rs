@@ -0,0 +1,60 @@
+//! Measures the soundness error of the sumcheck protocol over the field with 101 elements,
+//! where the bound v * d / |F| is a few percent, against cheating Provers.
+//!
+//! cargo run --release --example soundness
+
+use ark_ff::fields::{Fp64, MontBackend};
+use sumcheck::cheating::CheatingStrategy;
+use sumcheck::parser::parse_polynomial;
+use sumcheck::soundness::measure_soundness;
+
+/// The prime field with 101 elements
+#[derive(ark_ff::MontConfig)]
+#[modulus = "101"]
+#[generator = "2"]
+struct F101Config;
+type F101 = Fp64<MontBackend<F101Config, 1>>;
+
+const TRIALS: usize = 100_000;
+const SEED: u64 = 2024;
+
+fn main() {
+    let polynomials = [
+        "x0*x1 + 2*x1*x2 + x0",
+        "x0^2*x1 + 3*x1^2*x2 + x2^2 + 4",
+        "x0^3*x1^3 + x1^3*x2^3 + 5*x2^3*x3^3 + x3^3",
+    ];
+    let strategies = [
+        (
+            "adaptive",
+            CheatingStrategy::Adaptive {
+                offset: F101::from(1),
+            },
+        ),
+        (
+            "wrong sum",
+            CheatingStrategy::WrongSum {
+                offset: F101::from(1),
+            },
+        ),
+        (
+            "tweaked round 1",
+            CheatingStrategy::TweakCoefficient {
+                round: 1,
+                power: 1,
+                offset: F101::from(1),
+            },
+        ),
+    ];
+
+    for source in polynomials {
+        let polynomial = parse_polynomial::<F101>(source).expect("valid polynomial");
+        println!("{}", source);
+        for (name, strategy) in strategies {
+            match measure_soundness(&polynomial, strategy, TRIALS, SEED) {
+                Ok(report) => println!("  {:<16} {}", name, report),
+                Err(err) => println!("  {:<16} failed: {}", name, err),
+            }
+        }
+    }
+}
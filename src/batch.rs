@@ -0,0 +1,277 @@
+use crate::error::SumcheckError;
+use crate::prover::Prover;
+use crate::transcript::Transcript;
+use crate::verifier::{Subclaim, Verifier};
+use ark_ff::Field;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm},
+    univariate::SparsePolynomial as UniSparsePolynomial,
+    DenseMVPolynomial, Polynomial,
+};
+
+/// Label binding every batched transcript to this protocol
+const PROTOCOL_LABEL: &[u8] = b"batched_sumcheck";
+
+/// A single non-interactive proof for k sumcheck claims over up to `n` variables.
+/// The claims are combined with coefficients derived from the transcript, and the
+/// Prover ends by sending every polynomial's evaluation at the challenge point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchedSumcheckProof<F: Field> {
+    pub claimed_sums: Vec<F>,
+    pub round_polynomials: Vec<UniSparsePolynomial<F>>,
+    pub final_evaluations: Vec<F>, // f_j at the challenge point, one per polynomial
+}
+
+/// Absorbs every instance, polynomial included, and derives one combination coefficient per
+/// polynomial. Were the polynomials not absorbed, a Prover could choose them after seeing
+/// the coefficients, so that the wrong claims cancel out in the combination.
+fn new_transcript<F: Field>(
+    claimed_sums: &[F],
+    polynomials: &[SparsePolynomial<F, SparseTerm>],
+    max_degree: usize,
+) -> (Transcript, Vec<F>) {
+    let mut transcript = Transcript::new(PROTOCOL_LABEL);
+    transcript.append_message(b"num_instances", &(claimed_sums.len() as u64).to_le_bytes());
+    for (claimed_sum, polynomial) in claimed_sums.iter().zip(polynomials) {
+        transcript.append_polynomial_info(polynomial.num_vars, max_degree);
+        transcript.append_polynomial(polynomial);
+        transcript.append_claimed_sum(claimed_sum);
+    }
+    let coefficients = claimed_sums
+        .iter()
+        .map(|_| transcript.challenge(b"batching_coefficient"))
+        .collect();
+    (transcript, coefficients)
+}
+
+/// Sum of the combined polynomial over the padded hypercube. A polynomial in `n_j` of the
+/// `n` variables does not depend on the last `n - n_j`, so its sum is counted 2^(n - n_j) times.
+fn combined_claim<F: Field>(claimed_sums: &[F], num_variables: &[usize], coefficients: &[F]) -> F {
+    let padded_variables = num_variables.iter().copied().max().unwrap_or(0);
+    claimed_sums
+        .iter()
+        .zip(num_variables)
+        .zip(coefficients)
+        .map(|((claimed_sum, &n_j), coefficient)| {
+            let padding = F::from(2u64).pow([(padded_variables - n_j) as u64]);
+            *coefficient * claimed_sum * padding
+        })
+        .sum()
+}
+
+/// Proves the sum of every polynomial over its boolean hypercube with one sumcheck over
+/// sum_j c_j * f_j, where polynomials with fewer variables are padded with unused ones.
+/// Returns the proof and the challenge point over the largest number of variables; verify it
+/// against the same polynomials.
+pub fn prove_batched<F: Field>(
+    polynomials: &[SparsePolynomial<F, SparseTerm>],
+) -> Result<(BatchedSumcheckProof<F>, Vec<F>), SumcheckError<F>> {
+    if polynomials.is_empty() {
+        return Err(SumcheckError::InvalidParameters(
+            "at least one polynomial is needed".to_string(),
+        ));
+    }
+
+    let num_variables: Vec<usize> = polynomials.iter().map(|poly| poly.num_vars).collect();
+    let padded_variables = num_variables.iter().copied().max().unwrap_or(0);
+    let max_degree = polynomials
+        .iter()
+        .map(|poly| poly.degree())
+        .max()
+        .unwrap_or(0);
+    let claimed_sums: Vec<F> = polynomials
+        .iter()
+        .map(|poly| Prover::new_with_polynomial(poly.clone()).sum_over_all_inputs())
+        .collect();
+
+    let (mut transcript, coefficients) = new_transcript(&claimed_sums, polynomials, max_degree);
+    transcript.append_polynomial_info(padded_variables, max_degree);
+
+    let terms = polynomials
+        .iter()
+        .zip(&coefficients)
+        .flat_map(|(poly, coefficient)| {
+            poly.terms
+                .iter()
+                .map(move |(coeff, term)| (*coeff * coefficient, term.clone()))
+        })
+        .collect();
+    let combined = SparsePolynomial::from_coefficients_vec(padded_variables, terms);
+    let mut prover = Prover::new_with_polynomial(combined);
+
+    let mut challenges = Vec::with_capacity(padded_variables);
+    let mut round_polynomials = Vec::with_capacity(padded_variables);
+    for i in 0..padded_variables {
+        round_polynomials.push(prover.reduce_with_transcript(
+            i,
+            &mut challenges,
+            &mut transcript,
+        )?);
+    }
+
+    let final_evaluations = polynomials
+        .iter()
+        .map(|poly| poly.evaluate(&challenges[..poly.num_vars].to_vec()))
+        .collect();
+
+    Ok((
+        BatchedSumcheckProof {
+            claimed_sums,
+            round_polynomials,
+            final_evaluations,
+        },
+        challenges,
+    ))
+}
+
+/// Checks a batched proof about `polynomials`, every round polynomial having degree at most
+/// the largest degree among them. Returns one subclaim per polynomial: f_j evaluated at the
+/// first `num_vars` challenges must equal `final_evaluations[j]`.
+pub fn verify_batched<F: Field>(
+    proof: &BatchedSumcheckProof<F>,
+    polynomials: &[SparsePolynomial<F, SparseTerm>],
+) -> Result<Vec<Subclaim<F>>, SumcheckError<F>> {
+    // An empty batch proves nothing, and prove_batched refuses to produce one
+    if polynomials.is_empty() {
+        return Err(SumcheckError::InvalidParameters(
+            "at least one polynomial is needed".to_string(),
+        ));
+    }
+    let num_variables: Vec<usize> = polynomials.iter().map(|poly| poly.num_vars).collect();
+    let max_degree = polynomials
+        .iter()
+        .map(|poly| poly.degree())
+        .max()
+        .unwrap_or(0);
+    if proof.claimed_sums.len() != num_variables.len()
+        || proof.final_evaluations.len() != num_variables.len()
+    {
+        return Err(SumcheckError::InvalidParameters(format!(
+            "proof has {} claims and {} final evaluations for {} polynomials",
+            proof.claimed_sums.len(),
+            proof.final_evaluations.len(),
+            num_variables.len()
+        )));
+    }
+    let padded_variables = num_variables.iter().copied().max().unwrap_or(0);
+    if proof.round_polynomials.len() != padded_variables {
+        return Err(SumcheckError::WrongRoundCount {
+            expected: padded_variables,
+            got: proof.round_polynomials.len(),
+        });
+    }
+
+    let (mut transcript, coefficients) =
+        new_transcript(&proof.claimed_sums, polynomials, max_degree);
+    transcript.append_polynomial_info(padded_variables, max_degree);
+
+    let claim = combined_claim(&proof.claimed_sums, &num_variables, &coefficients);
+    let mut verifier =
+        Verifier::new(claim, vec![max_degree; padded_variables]).with_challenge_source(transcript);
+    for (round, polynomial) in proof.round_polynomials.iter().enumerate() {
+        let claim = verifier.current_claim;
+        verifier.verify_and_challenge(polynomial, round, &claim)?;
+    }
+    let subclaim = verifier.finalize()?;
+
+    // The final evaluations must be consistent with the combined claim
+    let combined_evaluation: F = proof
+        .final_evaluations
+        .iter()
+        .zip(&coefficients)
+        .map(|(evaluation, coefficient)| *evaluation * coefficient)
+        .sum();
+    if combined_evaluation != subclaim.expected_value {
+        return Err(SumcheckError::FinalEvaluationMismatch {
+            expected: subclaim.expected_value,
+            got: combined_evaluation,
+        });
+    }
+
+    Ok(num_variables
+        .iter()
+        .zip(&proof.final_evaluations)
+        .map(|(&n_j, evaluation)| Subclaim {
+            point: subclaim.point[..n_j].to_vec(),
+            expected_value: *evaluation,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_polynomial;
+    use ark_test_curves::fp128::Fq;
+
+    fn sample_polynomials() -> Vec<SparsePolynomial<Fq, SparseTerm>> {
+        [
+            "3*x0^3*x1 + 3*x0*x2 + 2*x1*x2",
+            "x0*x1 + 7",
+            "5*x2^2*x3 - x0 + 1",
+        ]
+        .iter()
+        .map(|s| parse_polynomial(s).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_batched_proof_gives_one_subclaim_per_polynomial() {
+        let polys = sample_polynomials();
+        let num_variables: Vec<usize> = polys.iter().map(|poly| poly.num_vars).collect();
+        assert_eq!(num_variables, vec![3, 2, 4]);
+
+        let (proof, challenges) = prove_batched(&polys).unwrap();
+        assert_eq!(proof.round_polynomials.len(), 4);
+
+        let subclaims = verify_batched(&proof, &polys).expect("honest proof");
+        for ((poly, subclaim), claimed_sum) in polys.iter().zip(&subclaims).zip(&proof.claimed_sums)
+        {
+            assert_eq!(subclaim.point, challenges[..poly.num_vars]);
+            assert_eq!(subclaim.check(poly), Ok(()));
+            assert_eq!(
+                *claimed_sum,
+                Prover::new_with_polynomial(poly.clone()).sum_over_all_inputs()
+            );
+        }
+    }
+
+    #[test]
+    fn test_batched_proof_rejects_any_wrong_claim() {
+        let polys = sample_polynomials();
+        let (proof, _) = prove_batched(&polys).unwrap();
+
+        for j in 0..polys.len() {
+            let mut tampered = proof.clone();
+            tampered.claimed_sums[j] += Fq::from(1);
+            assert!(verify_batched(&tampered, &polys).is_err());
+
+            // A wrong final evaluation is caught against the combined claim
+            let mut tampered = proof.clone();
+            tampered.final_evaluations[j] += Fq::from(1);
+            assert!(matches!(
+                verify_batched(&tampered, &polys),
+                Err(SumcheckError::FinalEvaluationMismatch { .. })
+            ));
+        }
+
+        assert!(verify_batched(&proof, &polys[..2]).is_err());
+
+        // Another polynomial with the same shape and sum gives other coefficients
+        let mut substituted = polys.clone();
+        substituted[1] = parse_polynomial("x0*x1 + x0 - x1 + 7").unwrap();
+        assert!(verify_batched(&proof, &substituted).is_err());
+        assert!(prove_batched::<Fq>(&[]).is_err());
+
+        // Nor is an empty batch accepted when verifying
+        let empty = BatchedSumcheckProof {
+            claimed_sums: vec![],
+            round_polynomials: vec![],
+            final_evaluations: vec![],
+        };
+        assert!(matches!(
+            verify_batched::<Fq>(&empty, &[]),
+            Err(SumcheckError::InvalidParameters(_))
+        ));
+    }
+}
@@ -0,0 +1,137 @@
+//! Where the Verifier's challenges come from. The same `Verifier` runs the interactive
+//! protocol with fresh randomness, the non-interactive one with a Fiat-Shamir `Transcript`,
+//! and deterministic tests with a seeded RNG or a scripted list of challenges.
+
+use crate::error::SumcheckError;
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::SparsePolynomial as UniSparsePolynomial;
+use ark_std::rand::{rngs::StdRng, thread_rng, SeedableRng};
+use std::collections::VecDeque;
+
+/// A source of round challenges. Every round message the Verifier accepts is passed to
+/// the source before the challenge of that round is drawn, so a source may bind its
+/// challenges to the messages; random sources ignore them.
+pub trait ChallengeSource<F: Field> {
+    /// Sees a round polynomial sent in coefficient form
+    fn absorb_polynomial(&mut self, _polynomial: &UniSparsePolynomial<F>) {}
+
+    /// Sees a round polynomial sent as its evaluations at 0..=degree
+    fn absorb_evaluations(&mut self, _evaluations: &[F]) {}
+
+    /// The challenge for the current round
+    fn next_challenge(&mut self) -> Result<F, SumcheckError<F>>;
+}
+
+/// A source that lends itself out, so that the caller keeps it once the Verifier is done,
+/// e.g. a transcript that goes on after the sumcheck
+impl<F: Field, C: ChallengeSource<F> + ?Sized> ChallengeSource<F> for &mut C {
+    fn absorb_polynomial(&mut self, polynomial: &UniSparsePolynomial<F>) {
+        (**self).absorb_polynomial(polynomial)
+    }
+
+    fn absorb_evaluations(&mut self, evaluations: &[F]) {
+        (**self).absorb_evaluations(evaluations)
+    }
+
+    fn next_challenge(&mut self) -> Result<F, SumcheckError<F>> {
+        (**self).next_challenge()
+    }
+}
+
+/// Fresh randomness for the interactive protocol, from `thread_rng`, which is seeded by
+/// the operating system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsChallenges;
+
+impl<F: Field> ChallengeSource<F> for OsChallenges {
+    fn next_challenge(&mut self) -> Result<F, SumcheckError<F>> {
+        Ok(F::rand(&mut thread_rng()))
+    }
+}
+
+/// Challenges from an RNG seeded once, so that a run can be replayed
+#[derive(Debug, Clone)]
+pub struct SeededChallenges {
+    rng: StdRng,
+}
+
+impl SeededChallenges {
+    pub fn new(seed: u64) -> Self {
+        SeededChallenges {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl<F: Field> ChallengeSource<F> for SeededChallenges {
+    fn next_challenge(&mut self) -> Result<F, SumcheckError<F>> {
+        Ok(F::rand(&mut self.rng))
+    }
+}
+
+/// Fiat-Shamir: every round message is absorbed into the transcript,
+/// and the challenge is derived from everything absorbed so far
+impl<F: Field> ChallengeSource<F> for Transcript {
+    fn absorb_polynomial(&mut self, polynomial: &UniSparsePolynomial<F>) {
+        self.append_round_polynomial(polynomial);
+    }
+
+    fn absorb_evaluations(&mut self, evaluations: &[F]) {
+        self.append_round_evaluations(evaluations);
+    }
+
+    fn next_challenge(&mut self) -> Result<F, SumcheckError<F>> {
+        Ok(self.challenge(b"round_challenge"))
+    }
+}
+
+/// A fixed list of challenges, handed out in order, for tests that need to choose them
+#[derive(Debug, Clone)]
+pub struct ScriptedChallenges<F: Field> {
+    challenges: VecDeque<F>,
+}
+
+impl<F: Field> ScriptedChallenges<F> {
+    pub fn new(challenges: Vec<F>) -> Self {
+        ScriptedChallenges {
+            challenges: challenges.into(),
+        }
+    }
+}
+
+impl<F: Field> ChallengeSource<F> for ScriptedChallenges<F> {
+    fn next_challenge(&mut self) -> Result<F, SumcheckError<F>> {
+        self.challenges.pop_front().ok_or_else(|| {
+            SumcheckError::InvalidParameters("no scripted challenge left".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::fp128::Fq;
+
+    #[test]
+    fn test_sources_are_deterministic_where_expected() {
+        let mut scripted = ScriptedChallenges::new(vec![Fq::from(3), Fq::from(5)]);
+        assert_eq!(scripted.next_challenge(), Ok(Fq::from(3)));
+        assert_eq!(scripted.next_challenge(), Ok(Fq::from(5)));
+        assert!(matches!(
+            scripted.next_challenge(),
+            Err(SumcheckError::InvalidParameters(_))
+        ));
+
+        let (mut a, mut b) = (SeededChallenges::new(9), SeededChallenges::new(9));
+        let c: Fq = a.next_challenge().unwrap();
+        assert_eq!(Ok(c), b.next_challenge());
+
+        // A transcript's challenge depends on the messages it absorbed
+        let (mut t1, mut t2) = (Transcript::new(b"test"), Transcript::new(b"test"));
+        ChallengeSource::<Fq>::absorb_evaluations(&mut t1, &[Fq::from(1)]);
+        ChallengeSource::<Fq>::absorb_evaluations(&mut t2, &[Fq::from(2)]);
+        let (c1, c2): (Fq, Fq) = (t1.next_challenge().unwrap(), t2.next_challenge().unwrap());
+        assert_ne!(c1, c2);
+    }
+}
@@ -0,0 +1,296 @@
+//! Dishonest provers for soundness testing. A `CheatingProver` wraps the honest `Prover`
+//! and departs from it according to a `CheatingStrategy`; a sound Verifier must reject
+//! every strategy, in the round given by the strategy or at the final evaluation check.
+
+use crate::error::SumcheckError;
+use crate::polynomial::variable_degrees;
+use crate::prover::Prover;
+use ark_ff::Field;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm},
+    univariate::SparsePolynomial as UniSparsePolynomial,
+    Polynomial,
+};
+use tracing::debug;
+
+/// How a `CheatingProver` departs from the honest protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheatingStrategy<F: Field> {
+    /// Claims the true sum plus `offset`, then answers every round honestly
+    WrongSum { offset: F },
+    /// Answers honestly, except that `offset` is added to the coefficient of X^`power`
+    /// in the polynomial of round `round`
+    TweakCoefficient {
+        round: usize,
+        power: usize,
+        offset: F,
+    },
+    /// Adds X^(bound + 1) - X to the polynomial of round `round`, or 2X - 1 when the bound
+    /// is 0 and X^1 - X would cancel out. It still sums to the claim over {0, 1}, but
+    /// exceeds the degree bound of the round.
+    DegreeTooHigh { round: usize },
+    /// Claims the true sum plus `offset`, and in every round sends the honest polynomial
+    /// plus an error term chosen so that the round passes the sum check. The error term
+    /// vanishes at 2..=bound + 1: if the challenge lands on one of these points, the claim
+    /// is true again and the remaining rounds are honest, which happens with probability
+    /// about bound / |F| per round. Otherwise, only the final evaluation check catches it.
+    Adaptive { offset: F },
+}
+
+/// A Prover that follows `strategy` instead of the protocol, for the same round interface
+/// as `Prover::reduce_to_univariate`
+pub struct CheatingProver<F: Field> {
+    pub prover: Prover<F>,
+    pub strategy: CheatingStrategy<F>,
+    pub degree_bounds: Vec<usize>, // The bounds a Verifier built with `from_polynomial` checks
+    claimed_sum: F,
+    last_message: Option<UniSparsePolynomial<F>>, // Polynomial sent in the previous round
+}
+
+impl<F: Field> CheatingProver<F> {
+    /// Create a cheating Prover for `polynomial`
+    pub fn new(polynomial: SparsePolynomial<F, SparseTerm>, strategy: CheatingStrategy<F>) -> Self {
+        let degree_bounds = variable_degrees(&polynomial);
+        let prover = Prover::new_with_polynomial(polynomial);
+        let claimed_sum = match strategy {
+            CheatingStrategy::WrongSum { offset } | CheatingStrategy::Adaptive { offset } => {
+                prover.sum_over_all_inputs() + offset
+            }
+            _ => prover.sum_over_all_inputs(),
+        };
+        CheatingProver {
+            prover,
+            strategy,
+            degree_bounds,
+            claimed_sum,
+            last_message: None,
+        }
+    }
+
+    /// The sum the Prover claims; the Verifier must be initialized with it
+    pub fn claimed_sum(&self) -> F {
+        self.claimed_sum
+    }
+
+    /// The polynomial sent for `target_var`, given the Verifier's previous challenges
+    pub fn reduce_to_univariate(
+        &mut self,
+        target_var: usize,
+        randoms: &Vec<F>,
+    ) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+        let honest = self.prover.reduce_to_univariate(target_var, randoms)?;
+
+        let message = match self.strategy {
+            CheatingStrategy::WrongSum { .. } => honest,
+            CheatingStrategy::TweakCoefficient {
+                round,
+                power,
+                offset,
+            } if round == target_var => honest + monomial(power, offset),
+            CheatingStrategy::DegreeTooHigh { round } if round == target_var => {
+                let bound = self.degree_bounds[target_var];
+                if bound == 0 {
+                    honest + monomial(1, F::from(2u64)) + monomial(0, -F::one())
+                } else {
+                    honest + monomial(bound + 1, F::one()) + monomial(1, -F::one())
+                }
+            }
+            CheatingStrategy::Adaptive { .. } => {
+                // The claim the Verifier holds, which the honest polynomial does not meet
+                let claim = match (&self.last_message, randoms.last()) {
+                    (Some(last), Some(challenge)) => last.evaluate(challenge),
+                    _ => self.claimed_sum,
+                };
+                let error = claim - honest.evaluate(&F::zero()) - honest.evaluate(&F::one());
+                honest + vanishing_error(self.degree_bounds[target_var], error)?
+            }
+            _ => honest,
+        };
+
+        debug!(?message, strategy = ?self.strategy, "cheating prover sends");
+        self.last_message = Some(message.clone());
+        Ok(message)
+    }
+}
+
+/// The polynomial coefficient * X^power
+fn monomial<F: Field>(power: usize, coefficient: F) -> UniSparsePolynomial<F> {
+    UniSparsePolynomial::from_coefficients_vec(vec![(power, coefficient)])
+}
+
+/// A polynomial of degree `degree` with e(0) + e(1) = `error`, vanishing at 2..=degree + 1
+fn vanishing_error<F: Field>(
+    degree: usize,
+    error: F,
+) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+    let mut roots = UniSparsePolynomial::from_coefficients_vec(vec![(0, F::one())]);
+    for a in 2..=degree as u64 + 1 {
+        roots = roots.mul(&UniSparsePolynomial::from_coefficients_vec(vec![
+            (0, -F::from(a)),
+            (1, F::one()),
+        ]));
+    }
+    let normalizer = (roots.evaluate(&F::zero()) + roots.evaluate(&F::one()))
+        .inverse()
+        .ok_or_else(|| {
+            SumcheckError::InvalidParameters(format!(
+                "no error term of degree {} sums to a non-zero value in this field",
+                degree
+            ))
+        })?;
+    Ok(roots.mul(&UniSparsePolynomial::from_coefficients_vec(vec![(
+        0,
+        error * normalizer,
+    )])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::ScriptedChallenges;
+    use crate::parser::parse_polynomial;
+    use crate::verifier::Verifier;
+    use ark_test_curves::fp128::Fq;
+
+    fn sample_polynomial() -> SparsePolynomial<Fq, SparseTerm> {
+        parse_polynomial("3*x0^3*x1 + 3*x0*x2 + 2*x1*x2^2 + 5").unwrap()
+    }
+
+    /// Runs the interactive protocol against `prover` and returns the Verifier's verdict
+    fn run(prover: &mut CheatingProver<Fq>) -> Result<(), SumcheckError<Fq>> {
+        let polynomial = prover.prover.polynomial.clone();
+        let mut verifier = Verifier::from_polynomial(&polynomial, prover.claimed_sum());
+        for i in 0..polynomial.num_vars {
+            let round_polynomial = prover.reduce_to_univariate(i, &verifier.challenge_values)?;
+            let claim = verifier.current_claim;
+            verifier.verify_and_challenge(&round_polynomial, i, &claim)?;
+        }
+        verifier.finalize_with_oracle(&polynomial).map(|_| ())
+    }
+
+    #[test]
+    fn test_verifier_rejects_wrong_sum() {
+        let mut prover = CheatingProver::new(
+            sample_polynomial(),
+            CheatingStrategy::WrongSum {
+                offset: Fq::from(1),
+            },
+        );
+        assert!(matches!(
+            run(&mut prover),
+            Err(SumcheckError::SumMismatch { round: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_verifier_rejects_tweaked_coefficient_in_any_round() {
+        for round in 0..3 {
+            for power in 0..=1 {
+                let mut prover = CheatingProver::new(
+                    sample_polynomial(),
+                    CheatingStrategy::TweakCoefficient {
+                        round,
+                        power,
+                        offset: Fq::from(7),
+                    },
+                );
+                assert!(matches!(
+                    run(&mut prover),
+                    Err(SumcheckError::SumMismatch { round: r, .. }) if r == round
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_verifier_rejects_degree_too_high() {
+        for (round, bound) in [3, 1, 2].into_iter().enumerate() {
+            let mut prover = CheatingProver::new(
+                sample_polynomial(),
+                CheatingStrategy::DegreeTooHigh { round },
+            );
+            assert_eq!(
+                run(&mut prover),
+                Err(SumcheckError::DegreeTooHigh {
+                    round,
+                    degree: bound + 1,
+                    bound
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_verifier_rejects_degree_too_high_for_absent_variable() {
+        // x1 does not appear, so its round polynomial must be constant
+        let mut prover = CheatingProver::new(
+            parse_polynomial("x0 + x2").unwrap(),
+            CheatingStrategy::DegreeTooHigh { round: 1 },
+        );
+        assert_eq!(
+            run(&mut prover),
+            Err(SumcheckError::DegreeTooHigh {
+                round: 1,
+                degree: 1,
+                bound: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_verifier_rejects_adaptive_prover_at_final_check() {
+        let mut prover = CheatingProver::new(
+            sample_polynomial(),
+            CheatingStrategy::Adaptive {
+                offset: Fq::from(1),
+            },
+        );
+        // Every round passes; over a large field the challenges miss the error's roots
+        assert!(matches!(
+            run(&mut prover),
+            Err(SumcheckError::FinalEvaluationMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_adaptive_prover_wins_when_a_challenge_hits_a_root() {
+        // The error term of round 1 vanishes at 2 and 3 for x1 of degree 2: from then on
+        // the claim is true, so the final check passes too
+        let poly = parse_polynomial::<Fq>("x0^2*x1 + 3*x1^2*x2 + x2^2 + 4").unwrap();
+        let mut prover = CheatingProver::new(
+            poly.clone(),
+            CheatingStrategy::Adaptive {
+                offset: Fq::from(1),
+            },
+        );
+        let mut verifier =
+            Verifier::from_polynomial(&poly, prover.claimed_sum()).with_challenge_source(
+                ScriptedChallenges::new(vec![Fq::from(11), Fq::from(3), Fq::from(17)]),
+            );
+        for i in 0..poly.num_vars {
+            let round_polynomial = prover
+                .reduce_to_univariate(i, &verifier.challenge_values)
+                .unwrap();
+            let claim = verifier.current_claim;
+            verifier
+                .verify_and_challenge(&round_polynomial, i, &claim)
+                .unwrap();
+        }
+        assert!(verifier.finalize_with_oracle(&poly).is_ok());
+    }
+
+    #[test]
+    fn test_vanishing_error_sums_to_error() {
+        for degree in 0..5 {
+            let error = vanishing_error(degree, Fq::from(9)).unwrap();
+            assert_eq!(error.degree(), degree);
+            assert_eq!(
+                error.evaluate(&Fq::from(0)) + error.evaluate(&Fq::from(1)),
+                Fq::from(9)
+            );
+            for a in 2..=degree as u64 + 1 {
+                assert_eq!(error.evaluate(&Fq::from(a)), Fq::from(0));
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use crate::error::{CircuitError, SumcheckError};
+use crate::mle::eq_table;
+use ark_ff::Field;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateType {
+    Add,
+    Mul,
+}
+
+/// A fan-in 2 gate reading two values of the layer below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gate {
+    pub gate_type: GateType,
+    pub left: usize,  // Index of the left input in the layer below
+    pub right: usize, // Index of the right input in the layer below
+}
+
+impl Gate {
+    pub fn add(left: usize, right: usize) -> Self {
+        Gate {
+            gate_type: GateType::Add,
+            left,
+            right,
+        }
+    }
+
+    pub fn mul(left: usize, right: usize) -> Self {
+        Gate {
+            gate_type: GateType::Mul,
+            left,
+            right,
+        }
+    }
+}
+
+/// A layered arithmetic circuit. `layers[0]` is the output layer, and every gate of
+/// `layers[i]` reads from `layers[i + 1]`; the gates of the last layer read the inputs.
+/// Layers are padded with zero-valued gates to a power of two, so that the values of layer i
+/// are a table over the boolean hypercube with `num_variables(i)` variables.
+/// The fields are only set by `Circuit::new`, so every circuit has passed its checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Circuit {
+    layers: Vec<Vec<Gate>>,
+    num_inputs: usize,
+}
+
+/// Number of variables of a layer with `size` values after padding
+fn padded_num_variables(size: usize) -> usize {
+    size.next_power_of_two().trailing_zeros() as usize
+}
+
+impl Circuit {
+    /// Creates a circuit, checking that every gate reads an existing value
+    pub fn new(layers: Vec<Vec<Gate>>, num_inputs: usize) -> Result<Self, CircuitError> {
+        if layers.is_empty() || layers.iter().any(|layer| layer.is_empty()) || num_inputs == 0 {
+            return Err(CircuitError(
+                "a circuit needs inputs and at least one gate in every layer".to_string(),
+            ));
+        }
+
+        for (i, layer) in layers.iter().enumerate() {
+            let below = layers.get(i + 1).map_or(num_inputs, |layer| layer.len());
+            if let Some(gate) = layer
+                .iter()
+                .find(|gate| gate.left >= below || gate.right >= below)
+            {
+                return Err(CircuitError(format!(
+                    "gate {:?} in layer {} reads past the {} values below it",
+                    gate, i, below
+                )));
+            }
+        }
+
+        Ok(Circuit { layers, num_inputs })
+    }
+
+    /// The gates of every layer, from the outputs down
+    pub fn layers(&self) -> &[Vec<Gate>] {
+        &self.layers
+    }
+
+    /// Number of values in the input layer, before padding
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    /// Number of layers of gates, not counting the inputs
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Number of variables of layer `i`; layer `depth()` is the input layer
+    pub fn num_variables(&self, layer: usize) -> usize {
+        match self.layers.get(layer) {
+            Some(gates) => padded_num_variables(gates.len()),
+            None => padded_num_variables(self.num_inputs),
+        }
+    }
+
+    /// Evaluates the circuit and returns the padded values of every layer, from the outputs
+    /// (index 0) down to the inputs (index `depth()`)
+    pub fn evaluate<F: Field>(&self, inputs: &[F]) -> Result<Vec<Vec<F>>, SumcheckError<F>> {
+        if inputs.len() != self.num_inputs {
+            return Err(SumcheckError::InvalidParameters(format!(
+                "circuit has {} inputs, got {}",
+                self.num_inputs,
+                inputs.len()
+            )));
+        }
+
+        let mut values = vec![Vec::new(); self.depth() + 1];
+        values[self.depth()] = inputs.to_vec();
+        values[self.depth()].resize(1 << self.num_variables(self.depth()), F::zero());
+        for i in (0..self.depth()).rev() {
+            let below = &values[i + 1];
+            let mut layer: Vec<F> = self.layers[i]
+                .iter()
+                .map(|gate| match gate.gate_type {
+                    GateType::Add => below[gate.left] + below[gate.right],
+                    GateType::Mul => below[gate.left] * below[gate.right],
+                })
+                .collect();
+            layer.resize(1 << self.num_variables(i), F::zero());
+            values[i] = layer;
+        }
+        Ok(values)
+    }
+
+    /// Evaluations of the wiring predicate of `gate_type` in layer `layer`, with its output
+    /// variables fixed to `r`: the entry at x + (y << k) is the multilinear extension of
+    /// "gate z has type `gate_type` and reads x and y" summed against eq(r, z),
+    /// where k is the number of variables of the layer below.
+    ///
+    /// The table is dense, with 2^(2k) entries: for a layer below of S values, that is S^2
+    /// field elements, however few gates the layer has. It is meant for small circuits,
+    /// up to about k = 12; larger ones need a sparse prover, such as the two-phase prover
+    /// of Libra, which runs in time linear in S.
+    pub fn wiring_table<F: Field>(&self, layer: usize, gate_type: GateType, r: &[F]) -> Vec<F> {
+        let k = self.num_variables(layer + 1);
+        let eq_r = eq_table(r);
+
+        let mut table = vec![F::zero(); 1 << (2 * k)];
+        for (z, gate) in self.layers[layer].iter().enumerate() {
+            if gate.gate_type == gate_type {
+                table[gate.left + (gate.right << k)] += eq_r[z];
+            }
+        }
+        table
+    }
+
+    /// The multilinear extension of the wiring predicate of `gate_type` in layer `layer`,
+    /// evaluated at (r, x, y), in time linear in the number of gates
+    pub fn wiring_evaluation<F: Field>(
+        &self,
+        layer: usize,
+        gate_type: GateType,
+        r: &[F],
+        x: &[F],
+        y: &[F],
+    ) -> F {
+        let (eq_r, eq_x, eq_y) = (eq_table(r), eq_table(x), eq_table(y));
+        self.layers[layer]
+            .iter()
+            .enumerate()
+            .filter(|(_, gate)| gate.gate_type == gate_type)
+            .map(|(z, gate)| eq_r[z] * eq_x[gate.left] * eq_y[gate.right])
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::fp128::Fq;
+
+    #[test]
+    fn test_evaluate_pads_layers() {
+        // (a + b) * (c * d) and (c * d) + (c * d), over 4 inputs
+        let circuit = Circuit::new(
+            vec![
+                vec![Gate::mul(0, 1), Gate::add(1, 1)],
+                vec![Gate::add(0, 1), Gate::mul(2, 3), Gate::add(0, 0)],
+            ],
+            4,
+        )
+        .unwrap();
+        assert_eq!(circuit.num_variables(0), 1);
+        assert_eq!(circuit.num_variables(1), 2);
+        assert_eq!(circuit.num_variables(2), 2);
+
+        let inputs: Vec<Fq> = [1u64, 2, 3, 4].into_iter().map(Fq::from).collect();
+        let values = circuit.evaluate(&inputs).unwrap();
+        assert_eq!(values[0], vec![Fq::from(36), Fq::from(24)]);
+        assert_eq!(
+            values[1],
+            vec![Fq::from(3), Fq::from(12), Fq::from(2), Fq::from(0)]
+        );
+    }
+
+    #[test]
+    fn test_wiring_evaluation_matches_table() {
+        let circuit = Circuit::new(
+            vec![vec![Gate::mul(0, 1), Gate::add(1, 0), Gate::mul(1, 1)]],
+            2,
+        )
+        .unwrap();
+        let r = vec![Fq::from(3), Fq::from(7)];
+        let table = circuit.wiring_table(0, GateType::Mul, &r);
+
+        // On the hypercube, the extension agrees with the table
+        for (index, entry) in table.iter().enumerate() {
+            let x = vec![Fq::from((index & 1) as u64)];
+            let y = vec![Fq::from((index >> 1) as u64)];
+            assert_eq!(
+                circuit.wiring_evaluation(0, GateType::Mul, &r, &x, &y),
+                *entry
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_wiring() {
+        assert!(Circuit::new(vec![vec![Gate::add(0, 2)]], 2).is_err());
+        assert!(Circuit::new(vec![vec![]], 2).is_err());
+        let circuit = Circuit::new(vec![vec![Gate::add(0, 1)]], 2).unwrap();
+        assert!(circuit.evaluate(&[Fq::from(1)]).is_err());
+    }
+}
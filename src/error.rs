@@ -0,0 +1,97 @@
+use std::fmt;
+
+/// Reasons why the Sumcheck Protocol can reject a proof or refuse its inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SumcheckError<F> {
+    /// g(0) + g(1) of the round polynomial does not match the previous claim
+    SumMismatch { round: usize, expected: F, got: F },
+    /// The round polynomial exceeds the degree bound of its variable
+    DegreeTooHigh {
+        round: usize,
+        degree: usize,
+        bound: usize,
+    },
+    /// The proof does not contain one round polynomial per variable
+    WrongRoundCount { expected: usize, got: usize },
+    /// The polynomial evaluated at the challenge point differs from the final claim
+    FinalEvaluationMismatch { expected: F, got: F },
+    /// A variable index is not below the number of variables of the polynomial
+    VariableOutOfRange {
+        variable: usize,
+        num_variables: usize,
+    },
+    /// Parameters that make the requested operation impossible
+    InvalidParameters(String),
+    /// A serialized message could not be decoded, or a round message is not in the form
+    /// the protocol sends, such as evaluations without g(0) and g(1)
+    MalformedEncoding(String),
+    /// A polynomial string is not valid syntax; `position` is the byte offset of the problem
+    Parse { position: usize, reason: String },
+}
+
+impl<F: fmt::Display> fmt::Display for SumcheckError<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SumcheckError::SumMismatch {
+                round,
+                expected,
+                got,
+            } => write!(
+                f,
+                "sum mismatch in round {}: expected {}, got {}",
+                round, expected, got
+            ),
+            SumcheckError::DegreeTooHigh {
+                round,
+                degree,
+                bound,
+            } => write!(
+                f,
+                "round {} polynomial has degree {}, above the bound {}",
+                round, degree, bound
+            ),
+            SumcheckError::WrongRoundCount { expected, got } => write!(
+                f,
+                "wrong number of rounds: expected {}, got {}",
+                expected, got
+            ),
+            SumcheckError::FinalEvaluationMismatch { expected, got } => write!(
+                f,
+                "final evaluation mismatch: expected {}, got {}",
+                expected, got
+            ),
+            SumcheckError::VariableOutOfRange {
+                variable,
+                num_variables,
+            } => write!(
+                f,
+                "variable {} is out of range for {} variables",
+                variable, num_variables
+            ),
+            SumcheckError::InvalidParameters(reason) => {
+                write!(f, "invalid parameters: {}", reason)
+            }
+            SumcheckError::MalformedEncoding(reason) => {
+                write!(f, "malformed encoding: {}", reason)
+            }
+            SumcheckError::Parse { position, reason } => {
+                write!(f, "parse error at position {}: {}", position, reason)
+            }
+        }
+    }
+}
+
+impl<F: fmt::Debug + fmt::Display> std::error::Error for SumcheckError<F> {}
+
+/// Why `Circuit::new` refused a circuit. It does not depend on a field, so building a
+/// circuit needs no type annotation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitError(pub String);
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid circuit: {}", self.0)
+    }
+}
+
+impl std::error::Error for CircuitError {}
@@ -0,0 +1,330 @@
+use crate::circuit::{Circuit, GateType};
+use crate::error::SumcheckError;
+use crate::mle::mle_from_evaluations;
+use crate::polynomial::interpolate_evaluations;
+use crate::transcript::Transcript;
+use crate::verifier::Verifier;
+use crate::virtual_polynomial::{VirtualPolynomial, VirtualPolynomialProver};
+use ark_ff::Field;
+use ark_poly::Polynomial;
+use ark_serialize::CanonicalSerialize;
+
+/// Label binding every GKR transcript to this protocol
+const PROTOCOL_LABEL: &[u8] = b"gkr";
+
+/// Degree of the layer sumcheck: wiring predicate times two values of the layer below
+const LAYER_DEGREE: usize = 3;
+
+/// The Prover's messages for one layer of the circuit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GkrLayerProof<F: Field> {
+    /// Round polynomials of the layer sumcheck, as evaluations at 0..=3
+    pub round_evaluations: Vec<Vec<F>>,
+    /// W(l(t)) at t = 0..=k for the line l through the two points left by the sumcheck,
+    /// where W is the layer below and k its number of variables. W(l(0)) and W(l(1))
+    /// are the two claims that the line reduces to one.
+    pub line_evaluations: Vec<F>,
+}
+
+/// A non-interactive GKR proof that the circuit maps the inputs to `outputs`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GkrProof<F: Field> {
+    pub outputs: Vec<F>,
+    pub layers: Vec<GkrLayerProof<F>>,
+}
+
+/// Canonical compressed encoding of a list of field elements
+fn field_elements_bytes<F: Field>(elements: &[F]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    elements
+        .serialize_compressed(&mut bytes)
+        .expect("serializing field elements into a Vec cannot fail");
+    bytes
+}
+
+/// Starts the transcript shared by `prove_gkr` and `verify_gkr`, and derives the point
+/// at which the output layer is checked. The whole circuit is absorbed, gate by gate, so
+/// that the challenges are bound to its wiring and not only to its depth.
+fn new_transcript<F: Field>(
+    circuit: &Circuit,
+    inputs: &[F],
+    outputs: &[F],
+) -> (Transcript, Vec<F>) {
+    let mut transcript = Transcript::new(PROTOCOL_LABEL);
+    transcript.append_message(b"depth", &(circuit.depth() as u64).to_le_bytes());
+    transcript.append_message(b"num_inputs", &(circuit.num_inputs() as u64).to_le_bytes());
+    for layer in circuit.layers() {
+        let mut gates = Vec::with_capacity(17 * layer.len());
+        for gate in layer {
+            gates.push(match gate.gate_type {
+                GateType::Add => 0u8,
+                GateType::Mul => 1u8,
+            });
+            gates.extend_from_slice(&(gate.left as u64).to_le_bytes());
+            gates.extend_from_slice(&(gate.right as u64).to_le_bytes());
+        }
+        transcript.append_message(b"layer", &gates);
+    }
+    transcript.append_message(b"gkr_inputs", &field_elements_bytes(inputs));
+    transcript.append_message(b"gkr_outputs", &field_elements_bytes(outputs));
+    let r = (0..circuit.num_variables(0))
+        .map(|_| transcript.challenge(b"output_point"))
+        .collect();
+    (transcript, r)
+}
+
+/// Number of points at which W is sent along the line: its degree k, but at least
+/// t = 0 and t = 1 are always sent
+fn num_line_evaluations(num_variables: usize) -> usize {
+    num_variables.max(1) + 1
+}
+
+/// The point b + t * (c - b) on the line through b and c
+fn line_point<F: Field>(b: &[F], c: &[F], t: F) -> Vec<F> {
+    b.iter()
+        .zip(c)
+        .map(|(b_j, c_j)| *b_j + t * (*c_j - b_j))
+        .collect()
+}
+
+/// Proves that `circuit` evaluated on `inputs` gives the outputs in the returned proof.
+/// Layer i reduces a claim about W_i(r) to a claim about the layer below, W, with one
+/// sumcheck run through `VirtualPolynomialProver` over
+/// sum_(x, y) add_i(r, x, y) * (W(x) + W(y)) + mul_i(r, x, y) * W(x) * W(y).
+/// Every factor of this sumcheck is a dense table over (x, y), so time and memory are
+/// quadratic in the size of the largest layer; see `Circuit::wiring_table`.
+pub fn prove_gkr<F: Field>(
+    circuit: &Circuit,
+    inputs: &[F],
+) -> Result<GkrProof<F>, SumcheckError<F>> {
+    let values = circuit.evaluate(inputs)?;
+    let outputs = values[0][..circuit.layers()[0].len()].to_vec();
+    let (mut transcript, mut r) = new_transcript(circuit, inputs, &outputs);
+
+    let mut layers = Vec::with_capacity(circuit.depth());
+    for i in 0..circuit.depth() {
+        let k = circuit.num_variables(i + 1);
+        let below = &values[i + 1];
+
+        // Every factor is a table over (x, y), with x in the low k variables
+        let mut polynomial = VirtualPolynomial::new(2 * k);
+        let add = polynomial.add_mle(mle_from_evaluations(circuit.wiring_table(
+            i,
+            GateType::Add,
+            &r,
+        ))?)?;
+        let mul = polynomial.add_mle(mle_from_evaluations(circuit.wiring_table(
+            i,
+            GateType::Mul,
+            &r,
+        ))?)?;
+        let mask = (1 << k) - 1;
+        let w_x = polynomial.add_mle(mle_from_evaluations(
+            (0..1 << (2 * k)).map(|index| below[index & mask]).collect(),
+        )?)?;
+        let w_y = polynomial.add_mle(mle_from_evaluations(
+            (0..1 << (2 * k)).map(|index| below[index >> k]).collect(),
+        )?)?;
+        polynomial.add_product(F::one(), vec![add, w_x])?;
+        polynomial.add_product(F::one(), vec![add, w_y])?;
+        polynomial.add_product(F::one(), vec![mul, w_x, w_y])?;
+
+        let mut prover = VirtualPolynomialProver::new(&polynomial);
+        let mut challenges = Vec::with_capacity(2 * k);
+        let mut round_evaluations = Vec::with_capacity(2 * k);
+        for round in 0..2 * k {
+            round_evaluations.push(prover.reduce_with_transcript(
+                round,
+                &mut challenges,
+                &mut transcript,
+            )?);
+        }
+
+        // Reduce the claims about W(b) and W(c) to one claim on the line through them
+        let (b, c) = challenges.split_at(k);
+        let w = mle_from_evaluations(below.clone())?;
+        let line_evaluations: Vec<F> = (0..num_line_evaluations(k))
+            .map(|t| w.evaluate(&line_point(b, c, F::from(t as u64))))
+            .collect();
+        transcript.append_round_evaluations(&line_evaluations);
+        r = line_point(b, c, transcript.challenge(b"line_challenge"));
+
+        layers.push(GkrLayerProof {
+            round_evaluations,
+            line_evaluations,
+        });
+    }
+
+    Ok(GkrProof { outputs, layers })
+}
+
+/// Verifies that `circuit` maps `inputs` to `proof.outputs`. Each layer is checked with the
+/// sumcheck `Verifier`, whose final subclaim is discharged with the wiring predicates, which
+/// the Verifier evaluates itself, and the two values of the layer below sent by the Prover.
+/// Returns the verified outputs.
+pub fn verify_gkr<F: Field>(
+    circuit: &Circuit,
+    inputs: &[F],
+    proof: &GkrProof<F>,
+) -> Result<Vec<F>, SumcheckError<F>> {
+    if proof.layers.len() != circuit.depth() {
+        return Err(SumcheckError::WrongRoundCount {
+            expected: circuit.depth(),
+            got: proof.layers.len(),
+        });
+    }
+    if proof.outputs.len() != circuit.layers()[0].len() || inputs.len() != circuit.num_inputs() {
+        return Err(SumcheckError::InvalidParameters(format!(
+            "circuit has {} inputs and {} outputs, got {} and {}",
+            circuit.num_inputs(),
+            circuit.layers()[0].len(),
+            inputs.len(),
+            proof.outputs.len()
+        )));
+    }
+
+    let (mut transcript, mut r) = new_transcript(circuit, inputs, &proof.outputs);
+    let mut outputs = proof.outputs.clone();
+    outputs.resize(1 << circuit.num_variables(0), F::zero());
+    let mut claim = mle_from_evaluations(outputs)?.evaluate(&r);
+
+    for (i, layer) in proof.layers.iter().enumerate() {
+        let k = circuit.num_variables(i + 1);
+        if layer.round_evaluations.len() != 2 * k {
+            return Err(SumcheckError::WrongRoundCount {
+                expected: 2 * k,
+                got: layer.round_evaluations.len(),
+            });
+        }
+        if layer.line_evaluations.len() != num_line_evaluations(k) {
+            return Err(SumcheckError::InvalidParameters(format!(
+                "layer {} sends {} line evaluations, expected {}",
+                i,
+                layer.line_evaluations.len(),
+                num_line_evaluations(k)
+            )));
+        }
+
+        let mut verifier =
+            Verifier::new(claim, vec![LAYER_DEGREE; 2 * k]).with_challenge_source(&mut transcript);
+        for (round, evaluations) in layer.round_evaluations.iter().enumerate() {
+            let claim = verifier.current_claim;
+            verifier.verify_evaluations_and_challenge(evaluations, round, &claim)?;
+        }
+        let subclaim = verifier.finalize()?;
+
+        // Discharge the subclaim with W(b) = l(0) and W(c) = l(1) from the Prover
+        let (b, c) = subclaim.point.split_at(k);
+        let (w_b, w_c) = (layer.line_evaluations[0], layer.line_evaluations[1]);
+        let add = circuit.wiring_evaluation(i, GateType::Add, &r, b, c);
+        let mul = circuit.wiring_evaluation(i, GateType::Mul, &r, b, c);
+        let evaluation = add * (w_b + w_c) + mul * w_b * w_c;
+        if evaluation != subclaim.expected_value {
+            return Err(SumcheckError::FinalEvaluationMismatch {
+                expected: subclaim.expected_value,
+                got: evaluation,
+            });
+        }
+
+        transcript.append_round_evaluations(&layer.line_evaluations);
+        let t = transcript.challenge(b"line_challenge");
+        r = line_point(b, c, t);
+        claim = interpolate_evaluations(&layer.line_evaluations, t);
+    }
+
+    // The last claim is about the inputs, which the Verifier knows
+    let mut inputs = inputs.to_vec();
+    inputs.resize(1 << circuit.num_variables(circuit.depth()), F::zero());
+    let evaluation = mle_from_evaluations(inputs)?.evaluate(&r);
+    if evaluation != claim {
+        return Err(SumcheckError::FinalEvaluationMismatch {
+            expected: claim,
+            got: evaluation,
+        });
+    }
+
+    Ok(proof.outputs.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Gate;
+    use ark_test_curves::fp128::Fq;
+
+    /// Three layers over 5 inputs, with padding in every layer
+    fn sample_circuit() -> Circuit {
+        Circuit::new(
+            vec![
+                vec![Gate::mul(0, 1), Gate::add(2, 0), Gate::mul(2, 2)],
+                vec![Gate::add(0, 1), Gate::mul(1, 2), Gate::add(2, 2)],
+                vec![
+                    Gate::mul(0, 1),
+                    Gate::add(2, 3),
+                    Gate::mul(4, 4),
+                    Gate::add(1, 4),
+                ],
+            ],
+            5,
+        )
+        .unwrap()
+    }
+
+    fn sample_inputs() -> Vec<Fq> {
+        [3u64, 5, 7, 11, 13].into_iter().map(Fq::from).collect()
+    }
+
+    #[test]
+    fn test_gkr_accepts_correct_outputs() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let proof = prove_gkr(&circuit, &inputs).unwrap();
+
+        let expected = circuit.evaluate(&inputs).unwrap()[0][..3].to_vec();
+        assert_eq!(verify_gkr(&circuit, &inputs, &proof), Ok(expected));
+    }
+
+    #[test]
+    fn test_gkr_rejects_wrong_outputs_and_tampered_layers() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let proof = prove_gkr(&circuit, &inputs).unwrap();
+
+        let mut wrong_output = proof.clone();
+        wrong_output.outputs[1] += Fq::from(1);
+        assert!(verify_gkr(&circuit, &inputs, &wrong_output).is_err());
+
+        for i in 0..circuit.depth() {
+            let mut tampered = proof.clone();
+            tampered.layers[i].round_evaluations[0][2] += Fq::from(1);
+            assert!(verify_gkr(&circuit, &inputs, &tampered).is_err());
+
+            let mut tampered = proof.clone();
+            tampered.layers[i].line_evaluations[0] += Fq::from(1);
+            assert!(verify_gkr(&circuit, &inputs, &tampered).is_err());
+        }
+
+        // The proof is bound to the inputs it was produced for
+        let mut other_inputs = inputs;
+        other_inputs[4] += Fq::from(1);
+        assert!(verify_gkr(&circuit, &other_inputs, &proof).is_err());
+    }
+
+    #[test]
+    fn test_output_point_depends_on_wiring() {
+        let inputs = sample_inputs();
+        let outputs = vec![Fq::from(8), Fq::from(77)];
+        let (_, r): (_, Vec<Fq>) = new_transcript(
+            &Circuit::new(vec![vec![Gate::add(0, 1), Gate::mul(2, 3)]], 5).unwrap(),
+            &inputs,
+            &outputs,
+        );
+        // Same depth, inputs and outputs, but the gate reads its inputs the other way round
+        let (_, swapped): (_, Vec<Fq>) = new_transcript(
+            &Circuit::new(vec![vec![Gate::add(1, 0), Gate::mul(2, 3)]], 5).unwrap(),
+            &inputs,
+            &outputs,
+        );
+        assert_ne!(r, swapped);
+    }
+}
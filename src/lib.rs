@@ -1,7 +1,24 @@
+pub mod batch;
+pub mod challenge;
+pub mod cheating;
+pub mod circuit;
+pub mod error;
+pub mod gkr;
+pub mod mle;
+pub mod multilinear;
+mod parallel;
+pub mod parser;
 pub mod polynomial;
+pub mod proof;
 pub mod prover;
+pub mod session;
+pub mod soundness;
 pub mod tests;
+pub mod transcript;
 pub mod verifier;
+pub mod virtual_polynomial;
+pub mod wire;
+pub mod zerocheck;
 
 #[allow(dead_code)]
 const MAX_DEGREE: usize = 3;
@@ -0,0 +1,183 @@
+use ark_ff::fields::{Fp128, MontBackend};
+use ark_poly::multivariate::{SparsePolynomial, SparseTerm};
+use std::collections::HashMap;
+use std::fs;
+use std::process::ExitCode;
+use sumcheck::error::SumcheckError;
+use sumcheck::polynomial::generate_random_polynomial_from_seed;
+use sumcheck::proof::{prove, verify, SumcheckProof};
+use sumcheck::wire::WireFormat;
+
+/// Prime field used by the command line tool, p = 2^127 - 1
+#[derive(ark_ff::MontConfig)]
+#[modulus = "170141183460469231731687303715884105727"]
+#[generator = "43"]
+struct FqConfig;
+type Fq = Fp128<MontBackend<FqConfig, 2>>;
+
+const USAGE: &str = "\
+Usage:
+  sumcheck generate --seed <u64> [--variables <n>] [--degree <d>] [--terms <t>]
+                    [--output <file>] [--format json|hex]
+  sumcheck prove    --polynomial <file> [--output <file>] [--format json|hex]
+  sumcheck verify   --proof <file> --polynomial <file>
+
+Files are read in either format. Without --output, the result is written to stdout.
+verify exits with status 1 if the proof is rejected, and every command exits with
+status 2 on a usage error or an unreadable or malformed file.";
+
+/// Failures of the command line tool, each with its own exit status
+enum CliError {
+    Usage(String),
+    Io(String),
+    Rejected(SumcheckError<Fq>),
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Rejected(_) => 1,
+            CliError::Usage(_) | CliError::Io(_) => 2,
+        }
+    }
+}
+
+impl From<SumcheckError<Fq>> for CliError {
+    fn from(error: SumcheckError<Fq>) -> Self {
+        CliError::Rejected(error)
+    }
+}
+
+/// `--name value` options following the subcommand
+struct Options(HashMap<String, String>);
+
+impl Options {
+    fn parse(args: &[String], allowed: &[&str]) -> Result<Self, CliError> {
+        let mut options = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .filter(|name| allowed.contains(name))
+                .ok_or_else(|| CliError::Usage(format!("unexpected argument '{}'", arg)))?;
+            let value = args
+                .next()
+                .ok_or_else(|| CliError::Usage(format!("missing value for --{}", name)))?;
+            options.insert(name.to_string(), value.clone());
+        }
+        Ok(Options(options))
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    fn required(&self, name: &str) -> Result<&str, CliError> {
+        self.get(name)
+            .ok_or_else(|| CliError::Usage(format!("missing required option --{}", name)))
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str, default: Option<T>) -> Result<T, CliError> {
+        match self.get(name) {
+            Some(value) => value.parse().map_err(|_| {
+                CliError::Usage(format!("--{} expects a number, got '{}'", name, value))
+            }),
+            None => default
+                .ok_or_else(|| CliError::Usage(format!("missing required option --{}", name))),
+        }
+    }
+}
+
+/// Reads a JSON or hex encoded message, telling the two apart by the leading '{'
+fn read_message<T: WireFormat<Fq>>(path: &str) -> Result<T, CliError> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| CliError::Io(format!("reading {}: {}", path, e)))?;
+    let decoded = if contents.trim_start().starts_with('{') {
+        T::from_json(&contents)
+    } else {
+        T::from_hex(&contents)
+    };
+    decoded.map_err(|e| CliError::Io(format!("decoding {}: {}", path, e)))
+}
+
+fn write_message<T: WireFormat<Fq>>(message: &T, options: &Options) -> Result<(), CliError> {
+    let encoded = match options.get("format").unwrap_or("json") {
+        "json" => message.to_json(),
+        "hex" => message.to_hex(),
+        other => return Err(CliError::Usage(format!("unknown format '{}'", other))),
+    };
+    match options.get("output") {
+        Some(path) => fs::write(path, encoded + "\n")
+            .map_err(|e| CliError::Io(format!("writing {}: {}", path, e))),
+        None => {
+            println!("{}", encoded);
+            Ok(())
+        }
+    }
+}
+
+fn generate(args: &[String]) -> Result<(), CliError> {
+    let options = Options::parse(
+        args,
+        &["seed", "variables", "degree", "terms", "output", "format"],
+    )?;
+    let polynomial: SparsePolynomial<Fq, SparseTerm> = generate_random_polynomial_from_seed(
+        options.number("seed", None)?,
+        options.number("variables", Some(3))?,
+        options.number("degree", Some(3))?,
+        options.number("terms", Some(10))?,
+    )
+    .map_err(|e| CliError::Usage(e.to_string()))?;
+    write_message(&polynomial, &options)
+}
+
+fn prove_command(args: &[String]) -> Result<(), CliError> {
+    let options = Options::parse(args, &["polynomial", "output", "format"])?;
+    let polynomial: SparsePolynomial<Fq, SparseTerm> =
+        read_message(options.required("polynomial")?)?;
+    // The polynomial is the user's input, so the library refusing it is a usage error,
+    // not a rejected proof
+    let (proof, _) = prove(&polynomial).map_err(|e| CliError::Usage(e.to_string()))?;
+    write_message(&proof, &options)
+}
+
+fn verify_command(args: &[String]) -> Result<(), CliError> {
+    let options = Options::parse(args, &["proof", "polynomial"])?;
+    let proof: SumcheckProof<Fq> = read_message(options.required("proof")?)?;
+    let polynomial: SparsePolynomial<Fq, SparseTerm> =
+        read_message(options.required("polynomial")?)?;
+
+    // The challenges depend on the polynomial, so it is needed for the rounds as well
+    // as for the final subclaim
+    let subclaim = verify(&proof, &polynomial)?;
+    subclaim.check(&polynomial)?;
+    println!("accepted: claimed sum {}", proof.claimed_sum);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("generate") => generate(&args[1..]),
+        Some("prove") => prove_command(&args[1..]),
+        Some("verify") => verify_command(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(CliError::Usage(format!("unknown command '{}'", other))),
+        None => Err(CliError::Usage("missing command".to_string())),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            match &error {
+                CliError::Usage(message) => eprintln!("error: {}\n\n{}", message, USAGE),
+                CliError::Io(message) => eprintln!("error: {}", message),
+                CliError::Rejected(reason) => eprintln!("rejected: {}", reason),
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
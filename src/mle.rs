@@ -0,0 +1,189 @@
+use crate::error::SumcheckError;
+use crate::polynomial::variable_degrees;
+use ark_ff::Field;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm, Term},
+    DenseMVPolynomial, DenseMultilinearExtension,
+};
+
+/// Builds a dense multilinear extension from its 2^v evaluations over the boolean hypercube.
+/// Bit `j` of a table index is the value of variable `j`.
+pub fn mle_from_evaluations<F: Field>(
+    evaluations: Vec<F>,
+) -> Result<DenseMultilinearExtension<F>, SumcheckError<F>> {
+    if !evaluations.len().is_power_of_two() {
+        return Err(SumcheckError::InvalidParameters(format!(
+            "evaluation table length must be a power of two, got {}",
+            evaluations.len()
+        )));
+    }
+
+    let num_vars = evaluations.len().trailing_zeros() as usize;
+    Ok(DenseMultilinearExtension::from_evaluations_vec(
+        num_vars,
+        evaluations,
+    ))
+}
+
+/// Evaluations of eq(r, x) over the boolean hypercube: the entry at index x is
+/// prod_j (r_j if bit j of x is set, 1 - r_j otherwise). Built variable by variable in O(2^v).
+pub fn eq_table<F: Field>(r: &[F]) -> Vec<F> {
+    let mut table = Vec::with_capacity(1 << r.len());
+    table.push(F::one());
+    for r_j in r {
+        let len = table.len();
+        for i in 0..len {
+            let with_bit = table[i] * r_j;
+            table[i] -= with_bit;
+            table.push(with_bit);
+        }
+    }
+    table
+}
+
+/// Converts a dense multilinear extension into the sparse coefficient representation.
+/// The coefficient of the monomial over a set of variables S is
+/// sum_{T ⊆ S} (-1)^{|S|-|T|} f(T), computed variable by variable in O(v * 2^v).
+pub fn mle_to_sparse<F: Field>(
+    mle: &DenseMultilinearExtension<F>,
+) -> SparsePolynomial<F, SparseTerm> {
+    let mut coefficients = mle.evaluations.clone();
+    for j in 0..mle.num_vars {
+        for i in 0..coefficients.len() {
+            if (i >> j) & 1 == 1 {
+                let without_j = coefficients[i ^ (1 << j)];
+                coefficients[i] -= without_j;
+            }
+        }
+    }
+
+    let terms = coefficients
+        .into_iter()
+        .enumerate()
+        .filter(|(_, coeff)| !coeff.is_zero())
+        .map(|(mask, coeff)| {
+            let vars = (0..mle.num_vars)
+                .filter(|j| (mask >> j) & 1 == 1)
+                .map(|j| (j, 1))
+                .collect();
+            (coeff, SparseTerm::new(vars))
+        })
+        .collect();
+
+    SparsePolynomial::from_coefficients_vec(mle.num_vars, terms)
+}
+
+/// Converts a sparse polynomial into a dense multilinear extension by evaluating it over
+/// the boolean hypercube. Fails if any variable has degree above 1, since the result
+/// would no longer be the same polynomial.
+pub fn sparse_to_mle<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+) -> Result<DenseMultilinearExtension<F>, SumcheckError<F>> {
+    if let Some(var) = variable_degrees(polynomial)
+        .iter()
+        .position(|&degree| degree > 1)
+    {
+        return Err(SumcheckError::InvalidParameters(format!(
+            "polynomial is not multilinear: variable {} has degree above 1",
+            var
+        )));
+    }
+
+    // Place every coefficient at the index of its variable set, then add each entry
+    // into all indices that contain its variables
+    let mut evaluations = vec![F::zero(); 1 << polynomial.num_vars];
+    for (coeff, term) in polynomial.terms() {
+        let mask = term.vars().iter().fold(0, |mask, var| mask | (1 << var));
+        evaluations[mask] += coeff;
+    }
+    for j in 0..polynomial.num_vars {
+        for i in 0..evaluations.len() {
+            if (i >> j) & 1 == 1 {
+                let without_j = evaluations[i ^ (1 << j)];
+                evaluations[i] += without_j;
+            }
+        }
+    }
+
+    Ok(DenseMultilinearExtension::from_evaluations_vec(
+        polynomial.num_vars,
+        evaluations,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_poly::Polynomial;
+    use ark_test_curves::fp128::Fq;
+
+    fn sample_polynomial() -> SparsePolynomial<Fq, SparseTerm> {
+        // 3*x0*x1 + 5*x0*x2 + 2*x1*x2 + x2 + 7
+        SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fq::from(3), SparseTerm::new(vec![(0, 1), (1, 1)])),
+                (Fq::from(5), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (Fq::from(2), SparseTerm::new(vec![(1, 1), (2, 1)])),
+                (Fq::from(1), SparseTerm::new(vec![(2, 1)])),
+                (Fq::from(7), SparseTerm::new(vec![])),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_sparse_to_mle_matches_hypercube_evaluations() {
+        let poly = sample_polynomial();
+        let mle = sparse_to_mle(&poly).unwrap();
+
+        for (i, evaluation) in mle.evaluations.iter().enumerate() {
+            let point = (0..3).map(|j| Fq::from((i >> j) as u64 & 1)).collect();
+            assert_eq!(*evaluation, poly.evaluate(&point));
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let poly = sample_polynomial();
+        let mle = sparse_to_mle(&poly).unwrap();
+        assert_eq!(mle_to_sparse(&mle), poly);
+
+        let evaluations: Vec<Fq> = (0..16).map(|i| Fq::from(i * i + 1)).collect();
+        let mle = mle_from_evaluations(evaluations).unwrap();
+        assert_eq!(sparse_to_mle(&mle_to_sparse(&mle)).unwrap(), mle);
+    }
+
+    #[test]
+    fn test_eq_table_matches_eq_polynomial() {
+        let r = vec![Fq::from(3), Fq::from(5), Fq::from(11)];
+        let table = eq_table(&r);
+        assert_eq!(table.len(), 8);
+        for (x, entry) in table.iter().enumerate() {
+            let expected: Fq = (0..3)
+                .map(|j| match (x >> j) & 1 {
+                    1 => r[j],
+                    _ => Fq::from(1) - r[j],
+                })
+                .product();
+            assert_eq!(*entry, expected);
+        }
+        // Summing eq(r, x) * f(x) over the hypercube evaluates the MLE of f at r
+        let mle = mle_from_evaluations((0..8).map(|i| Fq::from(i * i)).collect()).unwrap();
+        let sum: Fq = table
+            .iter()
+            .zip(&mle.evaluations)
+            .map(|(e, f)| *e * f)
+            .sum();
+        assert_eq!(sum, mle.evaluate(&r));
+    }
+
+    #[test]
+    fn test_rejects_non_multilinear_and_bad_lengths() {
+        let poly = SparsePolynomial::from_coefficients_vec(
+            2,
+            vec![(Fq::from(1), SparseTerm::new(vec![(1, 2)]))],
+        );
+        assert!(sparse_to_mle(&poly).is_err());
+        assert!(mle_from_evaluations(vec![Fq::from(1); 6]).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::SumcheckError;
+use crate::mle::mle_from_evaluations;
+use crate::parallel::{fold_range, fold_table};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::{univariate::SparsePolynomial as UniSparsePolynomial, DenseMultilinearExtension};
+use tracing::{debug, debug_span};
+
+/// Prover for a multilinear polynomial given as its table of evaluations over the
+/// boolean hypercube. Bit `j` of a table index is the value of variable `j`.
+///
+/// Instead of re-evaluating the polynomial over the hypercube in every round, the
+/// table is folded in place with each challenge, halving its size, so the whole
+/// protocol costs O(2^v) field operations.
+pub struct MultilinearProver<F: Field> {
+    pub evaluations: Vec<F>, // Bookkeeping table over the variables not fixed yet
+    pub num_variables: usize,
+    pub num_fixed: usize,   // Number of variables already fixed to a challenge
+    pub challenges: Vec<F>, // Challenges folded into the table so far
+}
+
+impl<F: Field> MultilinearProver<F> {
+    /// Create a new Prover from the 2^v evaluations of a multilinear polynomial
+    pub fn new(evaluations: Vec<F>) -> Result<Self, SumcheckError<F>> {
+        Ok(Self::from_mle(mle_from_evaluations(evaluations)?))
+    }
+
+    /// Create a new Prover from a dense multilinear extension
+    pub fn from_mle(mle: DenseMultilinearExtension<F>) -> Self {
+        MultilinearProver {
+            num_variables: mle.num_vars,
+            evaluations: mle.evaluations,
+            num_fixed: 0,
+            challenges: Vec::new(),
+        }
+    }
+
+    /// Calculates the sum of the polynomial over all possible input combinations of 0 and 1.
+    /// Once variables are fixed, this is the sum over the remaining ones.
+    pub fn sum_over_all_inputs(&self) -> F {
+        self.evaluations.iter().sum()
+    }
+
+    /// Fixes the lowest unfixed variable to `challenge`: every pair of entries that differ
+    /// only in that variable is replaced by the line through them, evaluated at `challenge`
+    fn fix_variable(&mut self, challenge: F) {
+        fold_table(&mut self.evaluations, challenge);
+        self.challenges.push(challenge);
+        self.num_fixed += 1;
+    }
+
+    /// Reduces the polynomial to a univariate polynomial in `target_var`, fixing the
+    /// previous variables to `randoms` and summing over the remaining ones.
+    /// Rounds must be run in order, since folding cannot be undone, and `randoms` must
+    /// start with the challenges of the earlier rounds.
+    pub fn reduce_to_univariate(
+        &mut self,
+        target_var: usize,
+        randoms: &[F],
+    ) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+        let _span = debug_span!("prover_round", round = target_var).entered();
+
+        if target_var >= self.num_variables {
+            return Err(SumcheckError::VariableOutOfRange {
+                variable: target_var,
+                num_variables: self.num_variables,
+            });
+        }
+        if randoms.len() != target_var || target_var < self.num_fixed {
+            return Err(SumcheckError::WrongRoundCount {
+                expected: self.num_fixed,
+                got: randoms.len(),
+            });
+        }
+        if randoms[..self.num_fixed] != self.challenges[..] {
+            return Err(SumcheckError::InvalidParameters(
+                "randoms differ from the challenges already folded into the table".to_string(),
+            ));
+        }
+
+        // Fold in the challenges received since the previous round
+        for &challenge in &randoms[self.num_fixed..] {
+            self.fix_variable(challenge);
+        }
+
+        // g(X) = sum_k (a_k + X * (b_k - a_k)), where a_k and b_k are the table entries
+        // with the target variable set to 0 and 1
+        let table = &self.evaluations;
+        let (constant, linear) = fold_range(
+            table.len() / 2,
+            || (F::zero(), F::zero()),
+            |(constant, linear), k| {
+                (
+                    constant + table[2 * k],
+                    linear + table[2 * k + 1] - table[2 * k],
+                )
+            },
+            |(c0, l0), (c1, l1)| (c0 + c1, l0 + l1),
+        );
+
+        let polynomial =
+            UniSparsePolynomial::from_coefficients_vec(vec![(0, constant), (1, linear)]);
+        debug!(
+            ?polynomial,
+            table_size = self.evaluations.len(),
+            "reduced to univariate polynomial"
+        );
+        Ok(polynomial)
+    }
+
+    /// Non-interactive round: reduces the polynomial for `target_var`, absorbs the result
+    /// into the transcript and appends the derived challenge to `randoms`
+    pub fn reduce_with_transcript(
+        &mut self,
+        target_var: usize,
+        randoms: &mut Vec<F>,
+        transcript: &mut Transcript,
+    ) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+        let polynomial = self.reduce_to_univariate(target_var, randoms)?;
+        transcript.append_round_polynomial(&polynomial);
+        randoms.push(transcript.challenge(b"round_challenge"));
+        Ok(polynomial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::Prover;
+    use ark_poly::{
+        multivariate::{SparsePolynomial, SparseTerm, Term},
+        DenseMVPolynomial, Polynomial,
+    };
+    use ark_std::{test_rng, UniformRand};
+    use ark_test_curves::fp128::Fq;
+
+    #[test]
+    fn test_matches_sparse_prover() {
+        // 3*x0*x1 + 5*x0*x2 + 2*x1*x2 + x2 + 7 is multilinear
+        let poly = SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fq::from(3), SparseTerm::new(vec![(0, 1), (1, 1)])),
+                (Fq::from(5), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (Fq::from(2), SparseTerm::new(vec![(1, 1), (2, 1)])),
+                (Fq::from(1), SparseTerm::new(vec![(2, 1)])),
+                (Fq::from(7), SparseTerm::new(vec![])),
+            ],
+        );
+        let evaluations = (0..8)
+            .map(|i| {
+                let point = (0..3).map(|j| Fq::from((i >> j) & 1)).collect();
+                poly.evaluate(&point)
+            })
+            .collect();
+
+        let mut sparse_prover = Prover::new_with_polynomial(poly);
+        let mut prover = MultilinearProver::new(evaluations).unwrap();
+        assert_eq!(
+            prover.sum_over_all_inputs(),
+            sparse_prover.sum_over_all_inputs()
+        );
+
+        let mut rng = test_rng();
+        let mut randoms = Vec::new();
+        for i in 0..3 {
+            assert_eq!(
+                prover.reduce_to_univariate(i, &randoms).unwrap(),
+                sparse_prover.reduce_to_univariate(i, &randoms).unwrap(),
+                "Round {} polynomials should match",
+                i
+            );
+            randoms.push(Fq::rand(&mut rng));
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_table_and_out_of_order_rounds() {
+        assert!(MultilinearProver::<Fq>::new(vec![Fq::from(1); 3]).is_err());
+
+        let mut prover = MultilinearProver::new(vec![Fq::from(1); 8]).unwrap();
+        let randoms = vec![Fq::from(2), Fq::from(3)];
+        prover.reduce_to_univariate(2, &randoms).unwrap();
+
+        // Rounds cannot be revisited once the table has been folded
+        assert!(prover.reduce_to_univariate(0, &[]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_challenges_other_than_the_folded_ones() {
+        let mut prover = MultilinearProver::new((0..8).map(Fq::from).collect()).unwrap();
+        prover.reduce_to_univariate(0, &[]).unwrap();
+        prover.reduce_to_univariate(1, &[Fq::from(2)]).unwrap();
+
+        let err = prover
+            .reduce_to_univariate(2, &[Fq::from(5), Fq::from(3)])
+            .unwrap_err();
+        assert!(matches!(err, SumcheckError::InvalidParameters(_)));
+
+        // The rejected call leaves the table untouched
+        prover
+            .reduce_to_univariate(2, &[Fq::from(2), Fq::from(3)])
+            .unwrap();
+        assert_eq!(prover.challenges, vec![Fq::from(2), Fq::from(3)]);
+    }
+}
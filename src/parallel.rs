@@ -0,0 +1,160 @@
+//! Loops over the boolean hypercube that are split across threads with the `parallel`
+//! feature. Field addition is associative and commutative, so both paths give identical
+//! results whatever order the partial sums are merged in.
+
+use ark_ff::Field;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Folds `accumulate` over `0..n` starting from `identity()`. With the `parallel` feature
+/// every thread folds its own part of the range, and the partial results are combined
+/// with `merge`.
+pub(crate) fn fold_range<T, I, A, M>(n: usize, identity: I, accumulate: A, merge: M) -> T
+where
+    T: Send,
+    I: Fn() -> T + Sync + Send,
+    A: Fn(T, usize) -> T + Sync + Send,
+    M: Fn(T, T) -> T + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        fold_range_parallel(n, identity, accumulate, merge)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        let _ = merge;
+        fold_range_serial(n, identity, accumulate)
+    }
+}
+
+/// The single-threaded `fold_range`, which never needs to merge
+#[cfg_attr(feature = "parallel", allow(dead_code))]
+fn fold_range_serial<T, I, A>(n: usize, identity: I, accumulate: A) -> T
+where
+    I: Fn() -> T,
+    A: Fn(T, usize) -> T,
+{
+    (0..n).fold(identity(), accumulate)
+}
+
+#[cfg(feature = "parallel")]
+fn fold_range_parallel<T, I, A, M>(n: usize, identity: I, accumulate: A, merge: M) -> T
+where
+    T: Send,
+    I: Fn() -> T + Sync + Send,
+    A: Fn(T, usize) -> T + Sync + Send,
+    M: Fn(T, T) -> T + Sync + Send,
+{
+    (0..n)
+        .into_par_iter()
+        .fold(&identity, &accumulate)
+        .reduce(&identity, &merge)
+}
+
+/// Adds `other` into `sums` entry by entry; the merge step for vectors of partial sums
+pub(crate) fn add_assign_vec<F: Field>(mut sums: Vec<F>, other: Vec<F>) -> Vec<F> {
+    for (sum, value) in sums.iter_mut().zip(other) {
+        *sum += value;
+    }
+    sums
+}
+
+/// Fixes the lowest variable of a bookkeeping table to `challenge`: every pair of entries
+/// (2k, 2k + 1) is replaced by the line through them, evaluated at `challenge`
+pub(crate) fn fold_table<F: Field>(table: &mut Vec<F>, challenge: F) {
+    #[cfg(feature = "parallel")]
+    {
+        fold_table_parallel(table, challenge)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        fold_table_serial(table, challenge)
+    }
+}
+
+/// The single-threaded `fold_table`, which folds in place
+#[cfg_attr(feature = "parallel", allow(dead_code))]
+fn fold_table_serial<F: Field>(table: &mut Vec<F>, challenge: F) {
+    let half = table.len() / 2;
+    for k in 0..half {
+        table[k] = table[2 * k] + challenge * (table[2 * k + 1] - table[2 * k]);
+    }
+    table.truncate(half);
+}
+
+#[cfg(feature = "parallel")]
+fn fold_table_parallel<F: Field>(table: &mut Vec<F>, challenge: F) {
+    *table = table
+        .par_chunks_exact(2)
+        .map(|pair| pair[0] + challenge * (pair[1] - pair[0]))
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::{test_rng, UniformRand};
+    use ark_test_curves::fp128::Fq;
+
+    #[test]
+    fn test_matches_sequential_loops() {
+        let mut rng = test_rng();
+        let table: Vec<Fq> = (0..1 << 12).map(|_| Fq::rand(&mut rng)).collect();
+
+        let sum = fold_range(
+            table.len(),
+            || vec![Fq::from(0); 2],
+            |mut sums, i| {
+                sums[i % 2] += table[i];
+                sums
+            },
+            add_assign_vec,
+        );
+        let even: Fq = table.iter().step_by(2).sum();
+        let odd: Fq = table.iter().skip(1).step_by(2).sum();
+        assert_eq!(sum, vec![even, odd]);
+
+        let challenge = Fq::rand(&mut rng);
+        let mut folded = table.clone();
+        fold_table(&mut folded, challenge);
+        let expected: Vec<Fq> = table
+            .chunks_exact(2)
+            .map(|pair| pair[0] + challenge * (pair[1] - pair[0]))
+            .collect();
+        assert_eq!(folded, expected);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_loops_match_serial_loops() {
+        let mut rng = test_rng();
+        let table: Vec<Fq> = (0..(1 << 12) + 6).map(|_| Fq::rand(&mut rng)).collect();
+
+        // Every thread starts from its own identity, so the merge must add up to the same
+        // result as a single fold, for a range that does not split evenly
+        let weighted_sums = |n| {
+            let accumulate = |mut sums: Vec<Fq>, i: usize| {
+                sums[i % 3] += table[i] * Fq::from(i as u64);
+                sums
+            };
+            (
+                fold_range_serial(n, || vec![Fq::from(0); 3], accumulate),
+                fold_range_parallel(n, || vec![Fq::from(0); 3], accumulate, add_assign_vec),
+            )
+        };
+        for n in [0, 1, 7, table.len()] {
+            let (serial, parallel) = weighted_sums(n);
+            assert_eq!(serial, parallel, "range of {}", n);
+        }
+
+        let mut serial = table[..1 << 12].to_vec();
+        let mut parallel = serial.clone();
+        for _ in 0..12 {
+            let challenge = Fq::rand(&mut rng);
+            fold_table_serial(&mut serial, challenge);
+            fold_table_parallel(&mut parallel, challenge);
+            assert_eq!(serial, parallel);
+        }
+        assert_eq!(serial.len(), 1);
+    }
+}
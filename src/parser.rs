@@ -0,0 +1,279 @@
+use crate::error::SumcheckError;
+use ark_ff::PrimeField;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm, Term},
+    DenseMVPolynomial,
+};
+use std::fmt;
+
+/// Parses a polynomial written as a sum of terms, such as `3*x0^3*x1 + 3*x0*x2 - 2*x1*x2 + 7`.
+/// A term is a product of decimal coefficients and variables `x<i>`, each optionally raised
+/// to a power with `^`. The polynomial has one variable more than the highest index used.
+pub fn parse_polynomial<F: PrimeField>(
+    input: &str,
+) -> Result<SparsePolynomial<F, SparseTerm>, SumcheckError<F>> {
+    let mut parser = Parser { input, position: 0 };
+    let mut terms = Vec::new();
+
+    let mut negative = parser.eat('-');
+    loop {
+        let (coefficient, term) = parser.term()?;
+        terms.push((if negative { -coefficient } else { coefficient }, term));
+
+        if parser.eat('+') {
+            negative = false;
+        } else if parser.eat('-') {
+            negative = true;
+        } else if parser.at_end() {
+            break;
+        } else {
+            return Err(parser.error("expected '+', '-' or '*'"));
+        }
+    }
+
+    let num_variables = terms
+        .iter()
+        .flat_map(|(_, term)| term.vars())
+        .map(|var| var + 1)
+        .max()
+        .unwrap_or(0);
+
+    Ok(SparsePolynomial::from_coefficients_vec(
+        num_variables,
+        terms,
+    ))
+}
+
+/// Cursor over the input; `position` is a byte offset, reported in parse errors
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.input[self.position..].chars().next()
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.peek().is_none()
+    }
+
+    /// Consumes `expected` if it is the next character
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error<F>(&self, reason: &str) -> SumcheckError<F> {
+        SumcheckError::Parse {
+            position: self.position,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Consumes a run of decimal digits, without skipping whitespace inside it
+    fn digits(&mut self) -> Option<&str> {
+        self.skip_whitespace();
+        let rest = &self.input[self.position..];
+        let len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return None;
+        }
+        self.position += len;
+        Some(&rest[..len])
+    }
+
+    fn integer<F>(&mut self, what: &str) -> Result<usize, SumcheckError<F>> {
+        let start = self.position;
+        let Some(digits) = self.digits() else {
+            return Err(self.error(&format!("expected {}", what)));
+        };
+        digits.parse().map_err(|_| SumcheckError::Parse {
+            position: start,
+            reason: format!("{} {} is too large", what, digits),
+        })
+    }
+
+    /// term := factor ('*' factor)*, factor := number | 'x' index ('^' power)?
+    fn term<F: PrimeField>(&mut self) -> Result<(F, SparseTerm), SumcheckError<F>> {
+        let mut coefficient = F::one();
+        let mut powers: Vec<(usize, usize)> = Vec::new();
+        // Ordering terms computes their total degree, which must not overflow
+        let mut degree = 0usize;
+        loop {
+            match self.peek() {
+                Some('x') => {
+                    let start = self.position;
+                    self.position += 1;
+                    let variable = self.integer("a variable index after 'x'")?;
+                    // The polynomial has variable + 1 variables
+                    if variable.checked_add(1).is_none() {
+                        return Err(SumcheckError::Parse {
+                            position: start,
+                            reason: format!("variable index {} is too large", variable),
+                        });
+                    }
+                    let power = if self.eat('^') {
+                        self.integer("an exponent after '^'")?
+                    } else {
+                        1
+                    };
+                    degree = degree
+                        .checked_add(power)
+                        .ok_or_else(|| SumcheckError::Parse {
+                            position: start,
+                            reason: "total degree of the term is too large".to_string(),
+                        })?;
+                    // A variable repeated in a term, as in x0*x0^2, adds up its powers,
+                    // which cannot overflow since the total degree does not
+                    match powers.iter_mut().find(|(v, _)| *v == variable) {
+                        Some((_, total)) => *total += power,
+                        None => powers.push((variable, power)),
+                    }
+                }
+                Some(c) if c.is_ascii_digit() => {
+                    let digits = self.digits().expect("a digit was peeked");
+                    // Decimal strings of any length are reduced modulo the field order
+                    coefficient *= digits
+                        .bytes()
+                        .fold(F::zero(), |acc, d| acc * F::from(10u8) + F::from(d - b'0'));
+                }
+                _ => return Err(self.error("expected a coefficient or a variable 'x<i>'")),
+            }
+
+            if !self.eat('*') {
+                return Ok((coefficient, SparseTerm::new(powers)));
+            }
+        }
+    }
+}
+
+/// Displays a polynomial in the syntax accepted by `parse_polynomial`, highest-degree
+/// terms first. A coefficient is printed as negative when that gives the smaller number.
+pub struct DisplayPolynomial<'a, F: PrimeField>(pub &'a SparsePolynomial<F, SparseTerm>);
+
+impl<F: PrimeField> fmt::Display for DisplayPolynomial<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        for (i, (coefficient, term)) in self.0.terms.iter().rev().enumerate() {
+            let negative = (-*coefficient).into_bigint() < coefficient.into_bigint();
+            let magnitude = if negative {
+                -*coefficient
+            } else {
+                *coefficient
+            };
+            match (i, negative) {
+                (0, false) => {}
+                (0, true) => write!(f, "-")?,
+                (_, false) => write!(f, " + ")?,
+                (_, true) => write!(f, " - ")?,
+            }
+
+            let mut factors = Vec::new();
+            if magnitude != F::one() || term.is_constant() {
+                factors.push(magnitude.to_string());
+            }
+            for (variable, power) in term.iter() {
+                factors.push(match power {
+                    1 => format!("x{}", variable),
+                    _ => format!("x{}^{}", variable, power),
+                });
+            }
+            write!(f, "{}", factors.join("*"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::fp128::Fq;
+
+    #[test]
+    fn test_parse_matches_hand_built_polynomial() {
+        let parsed: SparsePolynomial<Fq, SparseTerm> =
+            parse_polynomial("3*x0^3*x1 + 3*x0*x2 - 2 * x1*x2 + x2*x1 + 7").unwrap();
+        let expected = SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fq::from(3), SparseTerm::new(vec![(0, 3), (1, 1)])),
+                (Fq::from(3), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (-Fq::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
+                (Fq::from(7), SparseTerm::new(vec![])),
+            ],
+        );
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let input = "3*x0^3*x1 - x1*x2 + 3*x0*x2 + 5*x3^2 - 7";
+        let poly: SparsePolynomial<Fq, SparseTerm> = parse_polynomial(input).unwrap();
+        let printed = DisplayPolynomial(&poly).to_string();
+        assert_eq!(printed, "3*x0^3*x1 + 3*x0*x2 - x1*x2 + 5*x3^2 - 7");
+        assert_eq!(parse_polynomial::<Fq>(&printed).unwrap(), poly);
+
+        let zero: SparsePolynomial<Fq, SparseTerm> = parse_polynomial("x0 - x0").unwrap();
+        assert_eq!(DisplayPolynomial(&zero).to_string(), "0");
+    }
+
+    #[test]
+    fn test_parse_errors_report_position() {
+        let cases = [
+            ("3*x0 + ", 7),
+            ("3*x0 x1", 5),
+            ("3*y0", 2),
+            ("x0^", 3),
+            ("x", 1),
+        ];
+        for (input, expected_position) in cases {
+            match parse_polynomial::<Fq>(input) {
+                Err(SumcheckError::Parse { position, .. }) => {
+                    assert_eq!(position, expected_position, "input {:?}", input)
+                }
+                other => panic!("input {:?} should not parse, got {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_overflowing_indices_and_degrees() {
+        let cases = [
+            ("x18446744073709551615".to_string(), 0),
+            ("x0^18446744073709551615*x0^2".to_string(), 24),
+            ("x0^18446744073709551615*x1 + x2".to_string(), 24),
+            (format!("2*x1 + x{}", usize::MAX), 7),
+        ];
+        for (input, expected_position) in cases {
+            match parse_polynomial::<Fq>(&input) {
+                Err(SumcheckError::Parse { position, .. }) => {
+                    assert_eq!(position, expected_position, "input {:?}", input)
+                }
+                other => panic!("input {:?} should not parse, got {:?}", input, other),
+            }
+        }
+
+        // Repeated variables within the limits are merged
+        assert_eq!(
+            parse_polynomial::<Fq>("x0*x0^2*x1"),
+            parse_polynomial::<Fq>("x0^3*x1")
+        );
+    }
+}
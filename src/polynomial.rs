@@ -1,106 +1,271 @@
-use ark_ff::Field;
+use crate::error::SumcheckError;
+use ark_ff::{batch_inversion, Field};
 use ark_poly::{
     multivariate::{SparsePolynomial, SparseTerm, Term},
+    univariate::SparsePolynomial as UniSparsePolynomial,
     DenseMVPolynomial,
 };
-use ark_std::rand::{thread_rng, Rng};
-
-/// Helper function to generate variable combinations based on var_index
-/// Each `var_index` is treated as a bitmask where each bit represents a variable.
-/// The degree of each variable is randomly selected between 0 and `max_degree`.
-fn generate_term_from_var_index(num_variables: usize, max_degree: usize) -> Vec<(usize, usize)> {
+use ark_std::rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use std::collections::BTreeSet;
+use tracing::{debug, debug_span, trace};
+
+/// Helper function to generate the powers of one random term in the variables
+/// 0..num_variables. Each variable is included with probability 1/2, with a degree
+/// between 1 and `max_degree`; when none is included, the term is the constant 1.
+fn generate_random_term<R: Rng + ?Sized>(
+    rng: &mut R,
+    num_variables: usize,
+    max_degree: usize,
+) -> Vec<(usize, usize)> {
     let mut vars = Vec::new();
-    let mut rng = thread_rng();
-
-    // Iterate through each variable including the constant term (i = 0)
-    for i in 0..=num_variables {
-        if i == 0 {
-            // Special handling for the constant term
-            if rng.gen_bool(0.5) {
-                // 50% chance to include the constant term
-                let degree = rng.gen_range(1..=max_degree); // Ensure degree is at least 1 for the constant term
-                vars.push((0, degree));
-            }
-        } else {
-            // Randomly decide whether to include the variable
-            if rng.gen_bool(0.5) {
-                // 50% chance to include each variable
-                let degree = rng.gen_range(0..=max_degree);
-                if degree > 0 {
-                    vars.push((i, degree)); // Variable i is included with a random degree if the degree is greater than 0
-                }
-            }
+
+    for i in 0..num_variables {
+        // 50% chance to include each variable
+        if rng.gen_bool(0.5) {
+            vars.push((i, rng.gen_range(1..=max_degree)));
         }
     }
 
     vars
 }
 
-// active_variables means that maximum variable index + 1
-fn count_active_variables<F: Field>(terms: &[(F, SparseTerm)]) -> usize {
-    let mut max_variable_index = 0; // To track the maximum variable index
-
-    // Iterate over all terms to find the maximum variable index with non-zero degrees
-    for (_, term) in terms {
-        for &(variable, degree) in term.iter() {
-            // Update the maximum index if the degree is non-zero
-            if degree > 0 && variable > max_variable_index {
-                max_variable_index = variable;
-            }
-        }
-    }
+/// Function to generate a random sparse polynomial in `max_num_variables` variables.
+/// The polynomial always has `num_vars == max_num_variables`, even if some variable
+/// happens to appear in no term.
+pub fn generate_random_polynomial<F: Field>(
+    max_num_variables: usize,
+    max_degree: usize,
+    max_terms: usize,
+) -> Result<SparsePolynomial<F, SparseTerm>, SumcheckError<F>> {
+    generate_random_polynomial_with_rng(&mut thread_rng(), max_num_variables, max_degree, max_terms)
+}
 
-    // Return the maximum index + 1 to represent the number of variables
-    max_variable_index + 1
+/// Same as `generate_random_polynomial`, but reproducible: the same seed always
+/// generates the same polynomial
+pub fn generate_random_polynomial_from_seed<F: Field>(
+    seed: u64,
+    max_num_variables: usize,
+    max_degree: usize,
+    max_terms: usize,
+) -> Result<SparsePolynomial<F, SparseTerm>, SumcheckError<F>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    generate_random_polynomial_with_rng(&mut rng, max_num_variables, max_degree, max_terms)
 }
 
-/// Function to generate a random sparse polynomial with `max_num_variables` variables
-pub fn generate_random_polynomial<F: Field>(
+/// Same as `generate_random_polynomial`, drawing every random choice from `rng`
+pub fn generate_random_polynomial_with_rng<F: Field, R: Rng + ?Sized>(
+    rng: &mut R,
     max_num_variables: usize,
     max_degree: usize,
     max_terms: usize,
-) -> SparsePolynomial<F, SparseTerm> {
-    assert!(max_terms >= 1, "max_terms must be at least 1");
+) -> Result<SparsePolynomial<F, SparseTerm>, SumcheckError<F>> {
+    if max_terms < 1 {
+        return Err(SumcheckError::InvalidParameters(
+            "max_terms must be at least 1".to_string(),
+        ));
+    }
+    if max_degree < 1 {
+        return Err(SumcheckError::InvalidParameters(
+            "max_degree must be at least 1".to_string(),
+        ));
+    }
 
-    let mut num_variables = 0;
+    let _span = debug_span!(
+        "generate_random_polynomial",
+        max_num_variables,
+        max_degree,
+        max_terms
+    )
+    .entered();
 
-    let mut rng = thread_rng();
     let mut terms = Vec::new();
 
     // Randomly choose the number of terms to generate, which is less than or equal to max_terms
     let num_terms = rng.gen_range(1..=max_terms);
-    println!("Generating {} terms for the polynomial.", num_terms);
+    debug!(num_terms, "generating terms for the polynomial");
 
-    // Generate terms randomly based on num_terms
+    // Generate terms randomly based on num_terms; an empty term is a constant
     for i in 0..num_terms {
-        let vars = generate_term_from_var_index(max_num_variables, max_degree);
+        let vars = generate_random_term(rng, max_num_variables, max_degree);
+
+        trace!(term = i, ?vars, "generated term");
+
+        let coefficient = F::rand(rng); // Generate a random coefficient in the field F
+        terms.push((coefficient, SparseTerm::new(vars))); // Add the term to the list
+    }
+
+    debug!(?terms, "generated polynomial");
+
+    Ok(SparsePolynomial::from_coefficients_vec(
+        max_num_variables,
+        terms,
+    ))
+}
+
+/// Builder for random polynomials of a predictable shape. Unlike `generate_random_polynomial`,
+/// the polynomial has exactly `num_terms` distinct monomials, and the degrees and density
+/// of its terms are configurable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolynomialGenConfig {
+    num_variables: usize,
+    num_terms: usize,
+    degree_caps: Vec<usize>, // Maximum degree of each variable; 0 leaves it out of every term
+    density: f64,            // Probability that a variable appears in a term
+    multilinear: bool,
+    exclude_zero_coefficients: bool,
+    seed: Option<u64>,
+}
+
+impl PolynomialGenConfig {
+    /// Starts a configuration for `num_terms` terms in `num_variables` variables, each of
+    /// degree at most 1 and appearing in a term with probability 0.5
+    pub fn new(num_variables: usize, num_terms: usize) -> Self {
+        PolynomialGenConfig {
+            num_variables,
+            num_terms,
+            degree_caps: vec![1; num_variables],
+            density: 0.5,
+            multilinear: false,
+            exclude_zero_coefficients: true,
+            seed: None,
+        }
+    }
+
+    /// Caps the degree of every variable at `max_degree`
+    pub fn max_degree(mut self, max_degree: usize) -> Self {
+        self.degree_caps = vec![max_degree; self.num_variables];
+        self
+    }
 
-        // Debug: Print generated vars for the current term
-        println!("Term {}: vars = {:?}", i, vars);
+    /// Caps the degree of variable i at `degree_caps[i]`
+    pub fn degree_caps(mut self, degree_caps: Vec<usize>) -> Self {
+        self.degree_caps = degree_caps;
+        self
+    }
 
-        if !vars.is_empty() {
-            let mut crng = thread_rng();
-            // let coefficient = F::from(1_u128);
-            let coefficient = F::rand(&mut crng); // Generate a random coefficient in the field F
-            let term = SparseTerm::new(vars.clone()); // Create a SparseTerm for the given combination
-            terms.push((coefficient, term)); // Add the term to the list
+    /// Probability, between 0 and 1, that each variable appears in a term
+    pub fn density(mut self, density: f64) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Caps every degree at 1, whatever the degree caps say
+    pub fn multilinear(mut self, multilinear: bool) -> Self {
+        self.multilinear = multilinear;
+        self
+    }
+
+    /// Whether coefficients are drawn from the non-zero elements only (the default).
+    /// A zero coefficient removes its term, so the term count is then at most `num_terms`.
+    pub fn exclude_zero_coefficients(mut self, exclude: bool) -> Self {
+        self.exclude_zero_coefficients = exclude;
+        self
+    }
+
+    /// Makes `generate` reproducible: the same seed always gives the same polynomial
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Degree cap of `variable`, after the multilinear mode
+    fn cap(&self, variable: usize) -> usize {
+        if self.multilinear {
+            self.degree_caps[variable].min(1)
+        } else {
+            self.degree_caps[variable]
         }
+    }
 
-        // Recalculate num_variables based on active variables
-        num_variables = count_active_variables(&terms);
+    /// Number of distinct monomials that can be drawn, or None if it overflows a usize
+    fn num_monomials(&self) -> Option<usize> {
+        let mut caps = (0..self.num_variables).map(|variable| self.cap(variable));
+        if self.density == 0.0 {
+            Some(1)
+        } else if self.density == 1.0 {
+            // Every variable with a non-zero cap appears, with degree 1..=cap
+            caps.filter(|cap| *cap > 0)
+                .try_fold(1usize, usize::checked_mul)
+        } else {
+            caps.try_fold(1usize, |count, cap| count.checked_mul(cap + 1))
+        }
+    }
 
-        // Debug: Print current number of active variables
-        println!("Current active variables count: {}", num_variables);
+    fn validate<F: Field>(&self) -> Result<(), SumcheckError<F>> {
+        if self.degree_caps.len() != self.num_variables {
+            return Err(SumcheckError::InvalidParameters(format!(
+                "{} degree caps for {} variables",
+                self.degree_caps.len(),
+                self.num_variables
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.density) {
+            return Err(SumcheckError::InvalidParameters(format!(
+                "density must be between 0 and 1, got {}",
+                self.density
+            )));
+        }
+        if let Some(num_monomials) = self.num_monomials() {
+            if self.num_terms > num_monomials {
+                return Err(SumcheckError::InvalidParameters(format!(
+                    "{} terms requested, but only {} distinct monomials fit the configuration",
+                    self.num_terms, num_monomials
+                )));
+            }
+        }
+        Ok(())
+    }
 
-        // Debug: Print all current terms
-        println!("Current terms: {:?}", terms);
+    /// Generates a polynomial from the seed if one was set, from `thread_rng` otherwise
+    pub fn generate<F: Field>(&self) -> Result<SparsePolynomial<F, SparseTerm>, SumcheckError<F>> {
+        match self.seed {
+            Some(seed) => self.generate_with_rng(&mut StdRng::seed_from_u64(seed)),
+            None => self.generate_with_rng(&mut thread_rng()),
+        }
     }
 
-    // Final debug information
-    println!("Final number of variables: {}", num_variables);
-    println!("Final terms: {:?}", terms);
+    /// Same as `generate`, drawing every random choice from `rng` and ignoring the seed
+    pub fn generate_with_rng<F: Field, R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<SparsePolynomial<F, SparseTerm>, SumcheckError<F>> {
+        self.validate()?;
+        let _span = debug_span!(
+            "generate_configured_polynomial",
+            num_variables = self.num_variables,
+            num_terms = self.num_terms
+        )
+        .entered();
+
+        // Draw monomials until there are enough distinct ones
+        let mut monomials = BTreeSet::new();
+        let mut terms = Vec::with_capacity(self.num_terms);
+        while terms.len() < self.num_terms {
+            let mut powers = Vec::new();
+            for variable in 0..self.num_variables {
+                let cap = self.cap(variable);
+                if cap > 0 && rng.gen_bool(self.density) {
+                    powers.push((variable, rng.gen_range(1..=cap)));
+                }
+            }
+            let term = SparseTerm::new(powers);
+            if !monomials.insert(term.clone()) {
+                continue;
+            }
 
-    SparsePolynomial::from_coefficients_vec(num_variables, terms)
+            let mut coefficient = F::rand(rng);
+            while self.exclude_zero_coefficients && coefficient.is_zero() {
+                coefficient = F::rand(rng);
+            }
+            trace!(?term, "generated term");
+            terms.push((coefficient, term));
+        }
+
+        Ok(SparsePolynomial::from_coefficients_vec(
+            self.num_variables,
+            terms,
+        ))
+    }
 }
 
 /// Calculate the maximum number of variables used in a given SparsePolynomial
@@ -116,10 +281,109 @@ pub fn max_variables<F: Field>(polynomial: &SparsePolynomial<F, SparseTerm>) ->
     max_index + 1
 }
 
+/// Calculate the maximum degree of each variable in a given SparsePolynomial.
+/// These are the degree bounds of the univariate polynomials sent in each round.
+pub fn variable_degrees<F: Field>(polynomial: &SparsePolynomial<F, SparseTerm>) -> Vec<usize> {
+    let mut degrees = vec![0; polynomial.num_vars];
+    for (_, term) in &polynomial.terms {
+        for &(var_index, degree) in term.iter() {
+            if degree > degrees[var_index] {
+                degrees[var_index] = degree;
+            }
+        }
+    }
+    degrees
+}
+
+/// Barycentric weights of the nodes 0..n: w_i = 1 / prod_(j != i) (i - j),
+/// which is (-1)^(n - 1 - i) / (i! * (n - 1 - i)!)
+pub fn barycentric_weights<F: Field>(n: usize) -> Vec<F> {
+    let mut factorials = vec![F::one(); n.max(1)];
+    for k in 1..n {
+        factorials[k] = factorials[k - 1] * F::from(k as u64);
+    }
+
+    let mut weights: Vec<F> = (0..n)
+        .map(|i| {
+            let denominator = factorials[i] * factorials[n - 1 - i];
+            if (n - 1 - i) % 2 == 1 {
+                -denominator
+            } else {
+                denominator
+            }
+        })
+        .collect();
+    batch_inversion(&mut weights);
+    weights
+}
+
+/// Evaluates at `point` the unique polynomial of degree below `evaluations.len()`
+/// that takes the value `evaluations[i]` at `i`, with the barycentric formula
+/// p(x) = l(x) * sum_i w_i * p(i) / (x - i), where l(x) = prod_i (x - i).
+/// Needs a single field inversion however many evaluations there are.
+pub fn interpolate_evaluations<F: Field>(evaluations: &[F], point: F) -> F {
+    let n = evaluations.len();
+
+    // At a node, the formula would divide by zero; the value is given
+    let mut differences: Vec<F> = (0..n).map(|i| point - F::from(i as u64)).collect();
+    if let Some(i) = differences
+        .iter()
+        .position(|difference| difference.is_zero())
+    {
+        return evaluations[i];
+    }
+
+    let l: F = differences.iter().product();
+    batch_inversion(&mut differences);
+    let weights = barycentric_weights::<F>(n);
+    l * evaluations
+        .iter()
+        .zip(&weights)
+        .zip(&differences)
+        .map(|((evaluation, weight), inverse)| *evaluation * weight * inverse)
+        .sum::<F>()
+}
+
+/// The coefficient form of the unique polynomial of degree below `evaluations.len()` that
+/// takes the value `evaluations[i]` at `i`: sum_i w_i * p(i) * l(X) / (X - i), where
+/// l(X) = prod_i (X - i). Each quotient is a synthetic division, so this takes O(n^2).
+pub fn univariate_from_evaluations<F: Field>(evaluations: &[F]) -> UniSparsePolynomial<F> {
+    let n = evaluations.len();
+
+    // Coefficients of l(X), lowest degree first
+    let mut l = vec![F::one()];
+    for j in 0..n {
+        let node = F::from(j as u64);
+        l.insert(0, F::zero());
+        for k in 0..l.len() - 1 {
+            let shifted = l[k + 1] * node;
+            l[k] -= shifted;
+        }
+    }
+
+    let mut coefficients = vec![F::zero(); n];
+    for ((i, evaluation), weight) in evaluations
+        .iter()
+        .enumerate()
+        .zip(barycentric_weights::<F>(n))
+    {
+        let node = F::from(i as u64);
+        let scale = weight * evaluation;
+        // l(X) / (X - i), from the leading coefficient down
+        let mut quotient = F::zero();
+        for k in (0..n).rev() {
+            quotient = l[k + 1] + node * quotient;
+            coefficients[k] += scale * quotient;
+        }
+    }
+    UniSparsePolynomial::from_coefficients_vec(coefficients.into_iter().enumerate().collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ark_ff::Zero;
+    use ark_poly::Polynomial;
     use ark_test_curves::fp128::Fq; // Example field element for testing
 
     #[test]
@@ -129,7 +393,7 @@ mod tests {
         let max_terms = 10;
 
         // Generate a random polynomial
-        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms);
+        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms).unwrap();
 
         // Check that the number of terms is less than or equal to max_terms
         assert!(
@@ -147,7 +411,7 @@ mod tests {
                     "Degree should be between 1 and max_degree"
                 );
                 assert!(
-                    *var <= num_variables,
+                    *var < num_variables,
                     "Variable index should be within the number of variables"
                 );
             }
@@ -161,7 +425,7 @@ mod tests {
         let max_degree = 1;
         let max_terms = 1;
 
-        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms);
+        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms).unwrap();
 
         // The polynomial should have at most 1 term
         assert!(
@@ -170,11 +434,11 @@ mod tests {
         );
 
         // If a term exists, ensure it follows the constraints
-        if let Some((coeff, term)) = poly.terms().get(0) {
+        if let Some((coeff, term)) = poly.terms().first() {
             assert!(!coeff.is_zero(), "Coefficient should not be zero");
             for (var, deg) in term.iter() {
                 assert!(*deg > 0 && *deg <= max_degree, "Degree should be 1");
-                assert!(*var <= num_variables, "Variable index should be 0 or 1");
+                assert_eq!(*var, 0, "Variable index should be 0");
             }
         }
     }
@@ -186,7 +450,7 @@ mod tests {
         let max_degree = 10;
         let max_terms = 100;
 
-        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms);
+        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms).unwrap();
 
         // Check that the polynomial has the correct number of terms
         assert!(
@@ -204,7 +468,7 @@ mod tests {
                     "Degree should be between 1 and max_degree"
                 );
                 assert!(
-                    *var <= num_variables,
+                    *var < num_variables,
                     "Variable index should be within the number of variables"
                 );
             }
@@ -213,43 +477,56 @@ mod tests {
 
     #[test]
     fn test_constant_term_only() {
-        // Test case where only the constant term can be included
-        let num_variables = 1;
-        let max_degree = 5;
-        let max_terms = 1;
-
-        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms);
+        // Without variables, the only term the generator can draw is a constant
+        let poly = generate_random_polynomial::<Fq>(0, 5, 1).unwrap();
 
-        // There should be at most one term, which may be the constant term
+        assert_eq!(poly.num_vars, 0);
         assert!(
             poly.terms().len() <= 1,
             "Polynomial should have at most 1 term"
         );
+        if let Some((_, term)) = poly.terms().first() {
+            assert!(term.is_empty(), "The term should be the constant 1");
+        }
+    }
 
-        if let Some((_, term)) = poly.terms().get(0) {
-            // If the term is present, check if it's the constant term (i = 0)
-            for (var, _) in term.iter() {
-                assert!(
-                    *var == 0 || *var == 1,
-                    "Variable should be either 0 (constant) or 1 (first variable)"
-                );
-            }
+    #[test]
+    fn test_generated_num_vars_matches_request() {
+        let mut constants = 0;
+        for seed in 0..50 {
+            let poly = generate_random_polynomial_from_seed::<Fq>(seed, 3, 2, 10).unwrap();
+            assert_eq!(poly.num_vars, 3);
+            assert!(poly
+                .terms()
+                .iter()
+                .all(|(_, term)| term.iter().all(|(var, _)| *var < 3)));
+            constants += poly.terms().iter().filter(|(_, t)| t.is_empty()).count();
         }
+        // Every term is constant with probability 1/8, so constants do come up
+        assert!(constants > 0, "The generator should produce constant terms");
     }
 
     #[test]
-    fn test_max_terms_less_than_one_should_panic() {
+    fn test_max_terms_less_than_one_should_fail() {
         let num_variables = 3;
         let max_degree = 5;
 
-        // max_terms is set to 0, which should cause a panic
-        let result = std::panic::catch_unwind(|| {
-            generate_random_polynomial::<Fq>(num_variables, max_degree, 0);
-        });
+        // max_terms is set to 0, which should be rejected
+        let result = generate_random_polynomial::<Fq>(num_variables, max_degree, 0);
 
         assert!(
-            result.is_err(),
-            "Function should panic when max_terms is less than 1"
+            matches!(result, Err(SumcheckError::InvalidParameters(_))),
+            "Function should fail when max_terms is less than 1"
+        );
+    }
+
+    #[test]
+    fn test_max_degree_less_than_one_should_fail() {
+        let result = generate_random_polynomial::<Fq>(3, 0, 5);
+
+        assert!(
+            matches!(result, Err(SumcheckError::InvalidParameters(_))),
+            "Function should fail when max_degree is less than 1"
         );
     }
 
@@ -260,7 +537,7 @@ mod tests {
         let max_degree = 5;
         let max_terms = 1;
 
-        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms);
+        let poly = generate_random_polynomial::<Fq>(num_variables, max_degree, max_terms).unwrap();
 
         // The polynomial should contain exactly 1 term at most
         assert!(
@@ -268,4 +545,153 @@ mod tests {
             "Polynomial should have at most 1 term"
         );
     }
+
+    #[test]
+    fn test_config_gives_exact_shape() {
+        let config = PolynomialGenConfig::new(4, 12)
+            .degree_caps(vec![3, 0, 1, 2])
+            .density(0.7)
+            .seed(7);
+        let poly = config.generate::<Fq>().unwrap();
+
+        assert_eq!(poly.num_vars, 4);
+        assert_eq!(poly.terms().len(), 12);
+        for (coeff, term) in poly.terms() {
+            assert!(!coeff.is_zero());
+            for &(var, deg) in term.iter() {
+                assert!(deg >= 1 && deg <= [3, 0, 1, 2][var]);
+            }
+        }
+
+        // Seeded configurations are reproducible
+        assert_eq!(config.generate::<Fq>().unwrap(), poly);
+
+        let multilinear = config.multilinear(true).generate::<Fq>();
+        assert!(matches!(
+            multilinear,
+            Err(SumcheckError::InvalidParameters(_))
+        ));
+        let multilinear = PolynomialGenConfig::new(4, 12)
+            .max_degree(5)
+            .multilinear(true)
+            .seed(7)
+            .generate::<Fq>()
+            .unwrap();
+        assert_eq!(multilinear.terms().len(), 12);
+        assert!(variable_degrees(&multilinear).iter().all(|deg| *deg <= 1));
+    }
+
+    #[test]
+    fn test_config_density_extremes() {
+        // With density 0 the only monomial is the constant
+        let constant = PolynomialGenConfig::new(3, 1)
+            .density(0.0)
+            .generate::<Fq>()
+            .unwrap();
+        assert_eq!(constant.num_vars, 3);
+        assert!(constant.terms()[0].1.is_empty());
+
+        // With density 1 every term contains every variable
+        let dense = PolynomialGenConfig::new(3, 8)
+            .max_degree(2)
+            .density(1.0)
+            .generate::<Fq>()
+            .unwrap();
+        assert!(dense.terms().iter().all(|(_, term)| term.len() == 3));
+
+        for config in [
+            PolynomialGenConfig::new(3, 2).density(0.0),
+            PolynomialGenConfig::new(3, 9).max_degree(2).density(1.0),
+            PolynomialGenConfig::new(3, 1).density(1.5),
+            PolynomialGenConfig::new(3, 1).degree_caps(vec![1, 1]),
+        ] {
+            assert!(matches!(
+                config.generate::<Fq>(),
+                Err(SumcheckError::InvalidParameters(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_variable_degrees() {
+        let poly = SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fq::from(3), SparseTerm::new(vec![(0, 3), (1, 1)])),
+                (Fq::from(3), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (Fq::from(2), SparseTerm::new(vec![(1, 2)])),
+            ],
+        );
+
+        assert_eq!(variable_degrees(&poly), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_interpolate_evaluations() {
+        // p(x) = 2x^2 + 3x + 1 takes the values 1, 6, 15 at 0, 1, 2
+        let evaluations = [Fq::from(1), Fq::from(6), Fq::from(15)];
+
+        assert_eq!(
+            interpolate_evaluations(&evaluations, Fq::from(1)),
+            Fq::from(6)
+        );
+        assert_eq!(
+            interpolate_evaluations(&evaluations, Fq::from(5)),
+            Fq::from(66)
+        );
+
+        // A cubic interpolated from 4 points, compared with the Lagrange form
+        let cubic = |x: Fq| x * x * x * Fq::from(7) - x * Fq::from(4) + Fq::from(9);
+        let evaluations: Vec<Fq> = (0..4u64).map(|i| cubic(Fq::from(i))).collect();
+        let point = Fq::from(123456789u64);
+        assert_eq!(interpolate_evaluations(&evaluations, point), cubic(point));
+        assert_eq!(interpolate_evaluations(&[Fq::from(8)], point), Fq::from(8));
+    }
+
+    #[test]
+    fn test_univariate_from_evaluations() {
+        let cubic = UniSparsePolynomial::from_coefficients_vec(vec![
+            (0, Fq::from(7)),
+            (1, -Fq::from(2)),
+            (3, Fq::from(5)),
+        ]);
+        let evaluations: Vec<Fq> = (0..4u64).map(|t| cubic.evaluate(&Fq::from(t))).collect();
+        let interpolated = univariate_from_evaluations(&evaluations);
+        assert_eq!(interpolated.degree(), 3);
+        for t in 0..10u64 {
+            assert_eq!(
+                interpolated.evaluate(&Fq::from(t)),
+                cubic.evaluate(&Fq::from(t))
+            );
+        }
+
+        // Extra evaluations of a lower-degree polynomial give back its degree
+        let mut padded = evaluations;
+        padded.push(cubic.evaluate(&Fq::from(4)));
+        assert_eq!(univariate_from_evaluations(&padded).degree(), 3);
+        assert_eq!(univariate_from_evaluations::<Fq>(&[]).degree(), 0);
+    }
+
+    #[test]
+    fn test_barycentric_weights() {
+        // n = 3: 1 / ((0 - 1)(0 - 2)), 1 / ((1 - 0)(1 - 2)), 1 / ((2 - 0)(2 - 1))
+        let weights = barycentric_weights::<Fq>(3);
+        let expected = [Fq::from(2), -Fq::from(1), Fq::from(2)];
+        for (weight, inverse) in weights.iter().zip(expected) {
+            assert_eq!(*weight * inverse, Fq::from(1));
+        }
+    }
+
+    #[test]
+    fn test_same_seed_generates_same_polynomial() {
+        let seed = 0x5eed;
+        let poly1 = generate_random_polynomial_from_seed::<Fq>(seed, 4, 3, 10).unwrap();
+        let poly2 = generate_random_polynomial_from_seed::<Fq>(seed, 4, 3, 10).unwrap();
+
+        assert_eq!(
+            poly1, poly2,
+            "The same seed should replay the same polynomial"
+        );
+        assert_eq!(poly1.num_vars, poly2.num_vars);
+    }
 }
@@ -0,0 +1,571 @@
+use crate::error::SumcheckError;
+use crate::multilinear::MultilinearProver;
+use crate::polynomial::variable_degrees;
+use crate::prover::{check_num_variables, Prover};
+use crate::transcript::Transcript;
+use crate::verifier::{Subclaim, Verifier};
+use crate::virtual_polynomial::{VirtualPolynomial, VirtualPolynomialProver};
+use ark_ff::Field;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm},
+    univariate::SparsePolynomial as UniSparsePolynomial,
+    Polynomial,
+};
+
+/// Label binding every transcript to this protocol
+const PROTOCOL_LABEL: &[u8] = b"sumcheck";
+
+/// A standalone, non-interactive Sumcheck proof:
+/// the claimed sum and the univariate polynomial sent in every round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumcheckProof<F: Field> {
+    pub claimed_sum: F,
+    pub round_polynomials: Vec<UniSparsePolynomial<F>>,
+}
+
+/// Starts the transcript shared by `prove` and `verify`
+fn new_transcript<F: Field>(
+    claimed_sum: &F,
+    num_variables: usize,
+    max_degree: usize,
+) -> Transcript {
+    let mut transcript = Transcript::new(PROTOCOL_LABEL);
+    transcript.append_claimed_sum(claimed_sum);
+    transcript.append_polynomial_info(num_variables, max_degree);
+    transcript
+}
+
+/// Runs the Prover over every round and returns the proof together with the
+/// challenges derived from the transcript, i.e. the point of the final subclaim.
+/// The polynomial is absorbed into the transcript, so the proof must be verified
+/// against the same polynomial.
+pub fn prove<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+) -> Result<(SumcheckProof<F>, Vec<F>), SumcheckError<F>> {
+    check_num_variables(polynomial.num_vars)?;
+    let mut prover = Prover::new_with_polynomial(polynomial.clone());
+    let num_variables = prover.num_variables;
+
+    let claimed_sum = prover.sum_over_all_inputs();
+    let mut transcript = new_transcript(&claimed_sum, num_variables, polynomial.degree());
+    transcript.append_polynomial(polynomial);
+
+    let mut challenges = Vec::with_capacity(num_variables);
+    let mut round_polynomials = Vec::with_capacity(num_variables);
+    for i in 0..num_variables {
+        round_polynomials.push(prover.reduce_with_transcript(
+            i,
+            &mut challenges,
+            &mut transcript,
+        )?);
+    }
+
+    Ok((
+        SumcheckProof {
+            claimed_sum,
+            round_polynomials,
+        },
+        challenges,
+    ))
+}
+
+/// Same as `prove`, for a multilinear polynomial given by its 2^v evaluations over the
+/// boolean hypercube. Runs in O(2^v) time; verify the proof with `verify_multilinear`.
+pub fn prove_multilinear<F: Field>(
+    evaluations: Vec<F>,
+) -> Result<(SumcheckProof<F>, Vec<F>), SumcheckError<F>> {
+    let mut prover = MultilinearProver::new(evaluations)?;
+    let num_variables = prover.num_variables;
+
+    let claimed_sum = prover.sum_over_all_inputs();
+    let mut transcript = new_transcript(&claimed_sum, num_variables, 1);
+    transcript.append_multilinear(&prover.evaluations);
+
+    let mut challenges = Vec::with_capacity(num_variables);
+    let mut round_polynomials = Vec::with_capacity(num_variables);
+    for i in 0..num_variables {
+        round_polynomials.push(prover.reduce_with_transcript(
+            i,
+            &mut challenges,
+            &mut transcript,
+        )?);
+    }
+
+    Ok((
+        SumcheckProof {
+            claimed_sum,
+            round_polynomials,
+        },
+        challenges,
+    ))
+}
+
+/// Checks every round of a proof about `polynomial` and returns the subclaim that the
+/// polynomial, evaluated at the challenge point, equals the value reached in the last round.
+/// The round polynomial of each variable must have degree at most the degree of that
+/// variable in `polynomial`, as for `Verifier::from_polynomial`. The polynomial itself is
+/// needed, not only its shape, since the challenges are derived from it.
+pub fn verify<F: Field>(
+    proof: &SumcheckProof<F>,
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    let mut transcript =
+        new_transcript(&proof.claimed_sum, polynomial.num_vars, polynomial.degree());
+    transcript.append_polynomial(polynomial);
+    verify_rounds(proof, variable_degrees(polynomial), transcript)
+}
+
+/// Same as `verify`, for a proof from `prove_multilinear` about the multilinear polynomial
+/// with these evaluations over the boolean hypercube
+pub fn verify_multilinear<F: Field>(
+    proof: &SumcheckProof<F>,
+    evaluations: &[F],
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    if !evaluations.len().is_power_of_two() {
+        return Err(SumcheckError::InvalidParameters(format!(
+            "{} evaluations do not cover a boolean hypercube",
+            evaluations.len()
+        )));
+    }
+    let num_variables = evaluations.len().trailing_zeros() as usize;
+    let mut transcript = new_transcript(&proof.claimed_sum, num_variables, 1);
+    transcript.append_multilinear(evaluations);
+    verify_rounds(proof, vec![1; num_variables], transcript)
+}
+
+/// Checks the round polynomials of `proof` against the degree bound of every variable,
+/// with challenges drawn from the statement's transcript
+fn verify_rounds<F: Field>(
+    proof: &SumcheckProof<F>,
+    degree_bounds: Vec<usize>,
+    transcript: Transcript,
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    let num_variables = degree_bounds.len();
+    if proof.round_polynomials.len() != num_variables {
+        return Err(SumcheckError::WrongRoundCount {
+            expected: num_variables,
+            got: proof.round_polynomials.len(),
+        });
+    }
+
+    let mut verifier =
+        Verifier::new(proof.claimed_sum, degree_bounds).with_challenge_source(transcript);
+
+    for (round, polynomial) in proof.round_polynomials.iter().enumerate() {
+        let claim = verifier.current_claim;
+        verifier.verify_and_challenge(polynomial, round, &claim)?;
+    }
+
+    verifier.finalize()
+}
+
+/// A standalone, non-interactive Sumcheck proof for a `VirtualPolynomial`:
+/// the claimed sum and the evaluations at 0..=degree of every round polynomial
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualSumcheckProof<F: Field> {
+    pub claimed_sum: F,
+    pub round_evaluations: Vec<Vec<F>>,
+}
+
+/// Runs the `VirtualPolynomialProver` over every round and returns the proof together with
+/// the challenge point. Verify it with `verify_virtual` against the same polynomial.
+pub fn prove_virtual<F: Field>(
+    polynomial: &VirtualPolynomial<F>,
+) -> Result<(VirtualSumcheckProof<F>, Vec<F>), SumcheckError<F>> {
+    let mut prover = VirtualPolynomialProver::new(polynomial);
+    let num_variables = polynomial.num_variables();
+
+    let claimed_sum = polynomial.sum_over_all_inputs();
+    let mut transcript = new_transcript(&claimed_sum, num_variables, polynomial.max_degree());
+    transcript.append_virtual_polynomial(polynomial);
+
+    let mut challenges = Vec::with_capacity(num_variables);
+    let mut round_evaluations = Vec::with_capacity(num_variables);
+    for i in 0..num_variables {
+        round_evaluations.push(prover.reduce_with_transcript(
+            i,
+            &mut challenges,
+            &mut transcript,
+        )?);
+    }
+
+    Ok((
+        VirtualSumcheckProof {
+            claimed_sum,
+            round_evaluations,
+        },
+        challenges,
+    ))
+}
+
+/// Checks every round of a `VirtualSumcheckProof` about `polynomial` and returns the final
+/// subclaim. Every round polynomial must have degree at most `polynomial.max_degree()`.
+pub fn verify_virtual<F: Field>(
+    proof: &VirtualSumcheckProof<F>,
+    polynomial: &VirtualPolynomial<F>,
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    let (num_variables, max_degree) = (polynomial.num_variables(), polynomial.max_degree());
+    if proof.round_evaluations.len() != num_variables {
+        return Err(SumcheckError::WrongRoundCount {
+            expected: num_variables,
+            got: proof.round_evaluations.len(),
+        });
+    }
+
+    let mut transcript = new_transcript(&proof.claimed_sum, num_variables, max_degree);
+    transcript.append_virtual_polynomial(polynomial);
+    // At least g(0) and g(1) are sent, even for a constant round polynomial
+    let mut verifier = Verifier::new(proof.claimed_sum, vec![max_degree.max(1); num_variables])
+        .with_challenge_source(transcript);
+
+    for (round, evaluations) in proof.round_evaluations.iter().enumerate() {
+        let claim = verifier.current_claim;
+        verifier.verify_evaluations_and_challenge(evaluations, round, &claim)?;
+    }
+
+    verifier.finalize()
+}
+
+/// Drops g(1) from the evaluations of a round polynomial at 0..=d, since the Verifier
+/// derives it from the claim as g(1) = claim - g(0)
+pub fn compress_evaluations<F: Field>(evaluations: &[F]) -> Vec<F> {
+    evaluations
+        .iter()
+        .enumerate()
+        .filter(|&(t, _)| t != 1)
+        .map(|(_, evaluation)| *evaluation)
+        .collect()
+}
+
+/// Restores the evaluations at 0..=d from g(0), g(2), ..., g(d) and the claim
+/// that g(0) + g(1) must equal
+pub fn decompress_evaluations<F: Field>(compressed: &[F], claim: &F) -> Vec<F> {
+    let mut evaluations = compressed.to_vec();
+    if let Some(at_0) = compressed.first() {
+        evaluations.insert(1, *claim - at_0);
+    }
+    evaluations
+}
+
+/// A non-interactive Sumcheck proof whose round polynomials are sent as their evaluations
+/// at 0, 2, 3, ..., d. g(1) is left out, since it is determined by the claim.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedSumcheckProof<F: Field> {
+    pub claimed_sum: F,
+    pub round_evaluations: Vec<Vec<F>>,
+}
+
+impl<F: Field> VirtualSumcheckProof<F> {
+    /// Drops g(1) from every round; verify the result with `verify_compressed_virtual`
+    pub fn compress(&self) -> CompressedSumcheckProof<F> {
+        CompressedSumcheckProof {
+            claimed_sum: self.claimed_sum,
+            round_evaluations: self
+                .round_evaluations
+                .iter()
+                .map(|evaluations| compress_evaluations(evaluations))
+                .collect(),
+        }
+    }
+}
+
+/// Same as `prove`, with every round polynomial sent in compressed evaluation form.
+/// The proof must be verified with `verify_compressed` against the same polynomial.
+pub fn prove_compressed<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+) -> Result<(CompressedSumcheckProof<F>, Vec<F>), SumcheckError<F>> {
+    let mut prover = Prover::new_with_polynomial(polynomial.clone());
+    let num_variables = prover.num_variables;
+
+    let claimed_sum = prover.sum_over_all_inputs();
+    let mut transcript = new_transcript(&claimed_sum, num_variables, polynomial.degree());
+    transcript.append_polynomial(polynomial);
+
+    let mut challenges = Vec::with_capacity(num_variables);
+    let mut round_evaluations = Vec::with_capacity(num_variables);
+    for i in 0..num_variables {
+        let evaluations = prover.reduce_to_evaluations(i, &challenges)?;
+        transcript.append_round_evaluations(&evaluations);
+        challenges.push(transcript.challenge(b"round_challenge"));
+        round_evaluations.push(compress_evaluations(&evaluations));
+    }
+
+    Ok((
+        CompressedSumcheckProof {
+            claimed_sum,
+            round_evaluations,
+        },
+        challenges,
+    ))
+}
+
+/// Checks a `CompressedSumcheckProof` about `polynomial`. Every round's g(1) is derived from
+/// the current claim, so the sum check holds by construction, and a wrong claim is caught by
+/// the next round or by the final subclaim instead. The degree bound of every variable is
+/// still checked in its round, as for `verify`.
+pub fn verify_compressed<F: Field>(
+    proof: &CompressedSumcheckProof<F>,
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    let mut transcript =
+        new_transcript(&proof.claimed_sum, polynomial.num_vars, polynomial.degree());
+    transcript.append_polynomial(polynomial);
+    verify_compressed_rounds(proof, variable_degrees(polynomial), transcript)
+}
+
+/// Same as `verify_compressed`, for a proof from `VirtualSumcheckProof::compress`
+pub fn verify_compressed_virtual<F: Field>(
+    proof: &CompressedSumcheckProof<F>,
+    polynomial: &VirtualPolynomial<F>,
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    let (num_variables, max_degree) = (polynomial.num_variables(), polynomial.max_degree());
+    let mut transcript = new_transcript(&proof.claimed_sum, num_variables, max_degree);
+    transcript.append_virtual_polynomial(polynomial);
+    verify_compressed_rounds(proof, vec![max_degree; num_variables], transcript)
+}
+
+/// Checks the compressed rounds of `proof` against the degree bound of every variable,
+/// with challenges drawn from the statement's transcript
+fn verify_compressed_rounds<F: Field>(
+    proof: &CompressedSumcheckProof<F>,
+    degree_bounds: Vec<usize>,
+    transcript: Transcript,
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    let num_variables = degree_bounds.len();
+    if proof.round_evaluations.len() != num_variables {
+        return Err(SumcheckError::WrongRoundCount {
+            expected: num_variables,
+            got: proof.round_evaluations.len(),
+        });
+    }
+
+    // At least g(0) and g(1) are sent, even for a constant round polynomial
+    let degree_bounds = degree_bounds
+        .into_iter()
+        .map(|bound| bound.max(1))
+        .collect();
+    let mut verifier =
+        Verifier::new(proof.claimed_sum, degree_bounds).with_challenge_source(transcript);
+
+    for (round, compressed) in proof.round_evaluations.iter().enumerate() {
+        let claim = verifier.current_claim;
+        verifier.verify_evaluations_and_challenge(
+            &decompress_evaluations(compressed, &claim),
+            round,
+            &claim,
+        )?;
+    }
+
+    verifier.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mle::mle_from_evaluations;
+    use ark_poly::{multivariate::Term, DenseMVPolynomial};
+    use ark_test_curves::fp128::Fq;
+
+    fn sample_polynomial() -> SparsePolynomial<Fq, SparseTerm> {
+        SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fq::from(3), SparseTerm::new(vec![(0, 3), (1, 1)])),
+                (Fq::from(3), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (Fq::from(2), SparseTerm::new(vec![(1, 1), (2, 1)])),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_prove_and_verify() {
+        let poly = sample_polynomial();
+        let (proof, challenges) = prove(&poly).unwrap();
+
+        let subclaim = verify(&proof, &poly).expect("honest proof");
+        assert_eq!(subclaim.point, challenges);
+        assert_eq!(subclaim.check(&poly), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_claimed_sum() {
+        let poly = sample_polynomial();
+        let (mut proof, _) = prove(&poly).unwrap();
+        proof.claimed_sum += Fq::from(1);
+
+        let result = verify(&proof, &poly);
+        assert!(
+            matches!(result, Err(SumcheckError::SumMismatch { round: 0, .. })),
+            "A wrong claimed sum must be rejected in the first round"
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_missing_round() {
+        let poly = sample_polynomial();
+        let (mut proof, _) = prove(&poly).unwrap();
+        proof.round_polynomials.pop();
+
+        assert_eq!(
+            verify(&proof, &poly),
+            Err(SumcheckError::WrongRoundCount {
+                expected: 3,
+                got: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_degree_above_bound() {
+        let poly = sample_polynomial();
+        let (proof, _) = prove(&poly).unwrap();
+
+        // The first round polynomial has degree 3 in x0, above the degree of x0 in x0*x1 + x2
+        let lower_degree = SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fq::from(1), SparseTerm::new(vec![(0, 1), (1, 1)])),
+                (Fq::from(1), SparseTerm::new(vec![(2, 1)])),
+            ],
+        );
+        assert_eq!(
+            verify(&proof, &lower_degree),
+            Err(SumcheckError::DegreeTooHigh {
+                round: 0,
+                degree: 3,
+                bound: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_checks_the_degree_of_each_variable() {
+        let poly = sample_polynomial();
+        let (mut proof, _) = prove(&poly).unwrap();
+
+        // X^2 - X sums to 0 over {0, 1}, and degree 2 is within the total degree of 4,
+        // but x1 has degree 1
+        proof.round_polynomials[1] = &proof.round_polynomials[1]
+            + &UniSparsePolynomial::from_coefficients_vec(vec![
+                (1, -Fq::from(1)),
+                (2, Fq::from(1)),
+            ]);
+        assert_eq!(
+            verify(&proof, &poly),
+            Err(SumcheckError::DegreeTooHigh {
+                round: 1,
+                degree: 2,
+                bound: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_prove_rejects_too_many_variables() {
+        let poly = SparsePolynomial::from_coefficients_vec(
+            70,
+            vec![(Fq::from(1), SparseTerm::new(vec![(69, 1)]))],
+        );
+        assert!(matches!(
+            prove(&poly),
+            Err(SumcheckError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_prove_multilinear_with_many_variables() {
+        let num_variables = 16;
+        let evaluations: Vec<Fq> = (0..1u64 << num_variables).map(Fq::from).collect();
+        let (proof, challenges) = prove_multilinear(evaluations.clone()).unwrap();
+
+        let subclaim = verify_multilinear(&proof, &evaluations).expect("honest proof");
+        assert_eq!(subclaim.point, challenges);
+
+        // The multilinear extension evaluated at the challenge point is the fully folded table
+        let mut table = evaluations;
+        for r in &challenges {
+            table = table
+                .chunks_exact(2)
+                .map(|pair| pair[0] + *r * (pair[1] - pair[0]))
+                .collect();
+        }
+        assert_eq!(table[0], subclaim.expected_value);
+    }
+
+    #[test]
+    fn test_prove_and_verify_virtual() {
+        // A * B * C + 3 * A, with distinct small tables
+        let mut poly = VirtualPolynomial::new(4);
+        let tables: Vec<Vec<Fq>> = (1..=3u64)
+            .map(|k| (0..16u64).map(|i| Fq::from(k * i + k)).collect())
+            .collect();
+        let factors: Vec<usize> = tables
+            .into_iter()
+            .map(|table| poly.add_mle(mle_from_evaluations(table).unwrap()).unwrap())
+            .collect();
+        poly.add_product(Fq::from(1), factors.clone()).unwrap();
+        poly.add_product(Fq::from(3), vec![factors[0]]).unwrap();
+
+        let (proof, challenges) = prove_virtual(&poly).unwrap();
+        assert!(proof.round_evaluations.iter().all(|evals| evals.len() == 4));
+
+        let subclaim = verify_virtual(&proof, &poly).expect("honest proof");
+        assert_eq!(subclaim.point, challenges);
+        assert_eq!(subclaim.check(&poly), Ok(()));
+
+        // Dropping g(1) from every round leaves the same subclaim
+        let compressed = proof.compress();
+        assert!(compressed
+            .round_evaluations
+            .iter()
+            .all(|evals| evals.len() == 3));
+        assert_eq!(verify_compressed_virtual(&compressed, &poly), Ok(subclaim));
+
+        let mut tampered = proof;
+        tampered.round_evaluations[2][3] += Fq::from(1);
+        assert!(verify_virtual(&tampered, &poly).is_err());
+    }
+
+    #[test]
+    fn test_compressed_proof_matches_uncompressed() {
+        let poly = sample_polynomial();
+        let (proof, challenges) = prove_compressed(&poly).unwrap();
+        // One evaluation fewer than the degree bound requires
+        assert_eq!(proof.round_evaluations[0].len(), 3);
+
+        let subclaim = verify_compressed(&proof, &poly).unwrap();
+        assert_eq!(subclaim.point, challenges);
+        assert_eq!(subclaim.check(&poly), Ok(()));
+
+        // The first round polynomial is the same as in coefficient form;
+        // later rounds differ, since so do the transcripts
+        let (uncompressed, _) = prove(&poly).unwrap();
+        assert_eq!(proof.claimed_sum, uncompressed.claimed_sum);
+        let first_round = &uncompressed.round_polynomials[0];
+        for (t, evaluation) in [0u64, 2, 3].into_iter().zip(&proof.round_evaluations[0]) {
+            assert_eq!(*evaluation, first_round.evaluate(&Fq::from(t)));
+        }
+    }
+
+    #[test]
+    fn test_compressed_proof_rejects_wrong_claims() {
+        let poly = sample_polynomial();
+        let (proof, _) = prove_compressed(&poly).unwrap();
+
+        // A wrong claimed sum passes the derived sum checks, but not the final subclaim
+        let mut tampered = proof.clone();
+        tampered.claimed_sum += Fq::from(1);
+        let subclaim = verify_compressed(&tampered, &poly).unwrap();
+        assert!(subclaim.check(&poly).is_err());
+
+        // The first round polynomial has degree 3 in x0, two more evaluations make it 5,
+        // above the degree 4 of the polynomial
+        assert!(verify_compressed(&proof, &poly).is_ok());
+        let mut tampered = proof;
+        tampered.round_evaluations[0].extend([Fq::from(1), Fq::from(2)]);
+        assert!(matches!(
+            verify_compressed(&tampered, &poly),
+            Err(SumcheckError::DegreeTooHigh { round: 0, .. })
+        ));
+    }
+}
@@ -1,10 +1,15 @@
-use crate::polynomial::generate_random_polynomial;
+use crate::error::SumcheckError;
+use crate::mle::mle_to_sparse;
+use crate::parallel::{add_assign_vec, fold_range};
+use crate::polynomial::{generate_random_polynomial, generate_random_polynomial_from_seed};
+use crate::transcript::Transcript;
 use ark_ff::Field;
 use ark_poly::{
     multivariate::{SparsePolynomial, SparseTerm},
     univariate::SparsePolynomial as UniSparsePolynomial,
-    Polynomial,
+    DenseMultilinearExtension, Polynomial,
 };
+use tracing::{debug, debug_span, trace};
 
 pub struct Prover<F: Field> {
     pub polynomial: SparsePolynomial<F, SparseTerm>,
@@ -14,13 +19,30 @@ pub struct Prover<F: Field> {
 
 impl<F: Field> Prover<F> {
     /// Create a new Prover with a randomly generated polynomial
-    pub fn new(num_variables: usize, max_degree: usize, max_terms: usize) -> Self {
-        let polynomial = generate_random_polynomial(num_variables, max_degree, max_terms);
-        Prover {
+    pub fn new(
+        num_variables: usize,
+        max_degree: usize,
+        max_terms: usize,
+    ) -> Result<Self, SumcheckError<F>> {
+        let polynomial = generate_random_polynomial(num_variables, max_degree, max_terms)?;
+        Ok(Prover {
             polynomial: polynomial.clone(),
             num_variables: polynomial.num_vars,
             steps: Vec::new(),
-        }
+        })
+    }
+
+    /// Create a new Prover with a polynomial generated from `seed`,
+    /// so that a failing run can be replayed
+    pub fn new_seeded(
+        seed: u64,
+        num_variables: usize,
+        max_degree: usize,
+        max_terms: usize,
+    ) -> Result<Self, SumcheckError<F>> {
+        let polynomial =
+            generate_random_polynomial_from_seed(seed, num_variables, max_degree, max_terms)?;
+        Ok(Self::new_with_polynomial(polynomial))
     }
 
     /// Create a new Prover with a given polynomial
@@ -33,128 +55,187 @@ impl<F: Field> Prover<F> {
         }
     }
 
+    /// Create a new Prover from a dense multilinear extension,
+    /// converted to the sparse coefficient representation
+    pub fn new_with_mle(mle: &DenseMultilinearExtension<F>) -> Self {
+        Self::new_with_polynomial(mle_to_sparse(mle))
+    }
+
+    /// Reduces the polynomial to a univariate polynomial in `target_var`, fixing the
+    /// previous variables to `randoms` and summing over the remaining ones.
+    /// Nothing ties `randoms` to the Verifier's actual challenges; `session::ProverSession`
+    /// does, for callers that want the protocol order enforced.
     pub fn reduce_to_univariate(
         &mut self,
         target_var: usize,
         randoms: &Vec<F>,
-    ) -> UniSparsePolynomial<F> {
-        let mut coefficients = vec![F::zero(); self.polynomial.degree() + 1];
+    ) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+        let _span = debug_span!("prover_round", round = target_var).entered();
+
+        check_num_variables(self.num_variables)?;
+        if target_var >= self.num_variables {
+            return Err(SumcheckError::VariableOutOfRange {
+                variable: target_var,
+                num_variables: self.num_variables,
+            });
+        }
+        // One challenge is needed for every variable fixed in previous rounds
+        if randoms.len() != target_var {
+            return Err(SumcheckError::WrongRoundCount {
+                expected: target_var,
+                got: randoms.len(),
+            });
+        }
+
+        let num_coefficients = self.polynomial.degree() + 1;
         let v = self.num_variables;
 
         let round = target_var + 1;
 
         // Iterate over all input combinations for the remaining variables
-        for i in 0..2i32.pow((v - round) as u32) {
-            let mut inputs: Vec<F> = vec![];
-            // Add inputs from previous rounds (randoms)
-            inputs.extend(randoms);
-
-            // Generate inputs for the remaining variables
-            let mut counter = i;
-            for _ in 0..(v - round) {
-                if counter % 2 == 0 {
-                    inputs.push(F::from(0_u32));
-                } else {
-                    inputs.push(F::from(1_u32));
-                }
-                counter /= 2;
-            }
-
-            println!("input: {:?}", inputs);
-            println!("round: {:?}", round);
-
-            // Evaluate the polynomial at the current input combination
-            for (coeff, term) in &self.polynomial.terms {
-                let mut c_acc = F::one();
-                let mut degree_target = 0;
-                let mut has_target_var = false; // Flag to check if target_var is in the term
-
-                // Check each term's variables to determine if target_var is included
-                for (var_index, var_degree) in term.iter() {
-                    if *var_index == (target_var) {
-                        degree_target = *var_degree;
-                        has_target_var = true; // Mark that target_var is in the term
-                    } else if *var_index < (target_var) {
-                        c_acc *= inputs[*var_index].pow([*var_degree as u64]);
+        let coefficients = fold_range(
+            1 << (v - round),
+            || vec![F::zero(); num_coefficients],
+            |mut coefficients, i| {
+                let mut inputs: Vec<F> = vec![];
+                // Add inputs from previous rounds (randoms)
+                inputs.extend(randoms);
+
+                // Generate inputs for the remaining variables
+                let mut counter = i;
+                for _ in 0..(v - round) {
+                    if counter % 2 == 0 {
+                        inputs.push(F::from(0_u32));
                     } else {
-                        // Process variables other than target_var
-                        c_acc *= inputs[*var_index - 1].pow([*var_degree as u64]);
+                        inputs.push(F::from(1_u32));
                     }
+                    counter /= 2;
                 }
 
-                if !has_target_var {
-                    println!(
-                        "Adding to constant term: coeff = {:?}, c_acc = {:?}",
-                        coeff, c_acc
-                    );
-                    coefficients[0] += *coeff * c_acc;
-                } else {
-                    println!(
-                        "Adding to degree {:?}: coeff = {:?}, c_acc = {:?}",
-                        degree_target, coeff, c_acc
-                    );
-                    coefficients[degree_target] += *coeff * c_acc;
+                trace!(?inputs, "evaluating input combination");
+
+                // Evaluate the polynomial at the current input combination
+                for (coeff, term) in &self.polynomial.terms {
+                    let mut c_acc = F::one();
+                    let mut degree_target = 0;
+                    let mut has_target_var = false; // Flag to check if target_var is in the term
+
+                    // Check each term's variables to determine if target_var is included
+                    for (var_index, var_degree) in term.iter() {
+                        if *var_index == (target_var) {
+                            degree_target = *var_degree;
+                            has_target_var = true; // Mark that target_var is in the term
+                        } else if *var_index < (target_var) {
+                            c_acc *= inputs[*var_index].pow([*var_degree as u64]);
+                        } else {
+                            // Process variables other than target_var
+                            c_acc *= inputs[*var_index - 1].pow([*var_degree as u64]);
+                        }
+                    }
+
+                    if !has_target_var {
+                        trace!(?coeff, ?c_acc, "adding to constant term");
+                        coefficients[0] += *coeff * c_acc;
+                    } else {
+                        trace!(degree = degree_target, ?coeff, ?c_acc, "adding to degree");
+                        coefficients[degree_target] += *coeff * c_acc;
+                    }
                 }
-            }
-        }
+                coefficients
+            },
+            add_assign_vec,
+        );
 
         // Create the univariate polynomial from the coefficients
-        UniSparsePolynomial::from_coefficients_vec(coefficients.into_iter().enumerate().collect())
+        let polynomial = UniSparsePolynomial::from_coefficients_vec(
+            coefficients.into_iter().enumerate().collect(),
+        );
+        debug!(?polynomial, "reduced to univariate polynomial");
+        Ok(polynomial)
     }
 
-    /// Calculates the sum of the polynomial over all possible input combinations of 0 and 1
-    pub fn sum_over_all_inputs(&self) -> F {
-        println!("Debug: self.num_variables = {}", self.num_variables);
-        // let univariate_poly = self.convert_to_univariate();
-        // Generate all combinations of 0 and 1 for the number of variables
-        let combinations = Self::generate_combinations(self.num_variables);
+    /// Same as `reduce_to_univariate`, with the round polynomial sent as its evaluations
+    /// at 0..=d, where d is its degree but at least 1 so that g(0) and g(1) are always sent
+    pub fn reduce_to_evaluations(
+        &mut self,
+        target_var: usize,
+        randoms: &Vec<F>,
+    ) -> Result<Vec<F>, SumcheckError<F>> {
+        let polynomial = self.reduce_to_univariate(target_var, randoms)?;
+        Ok((0..=polynomial.degree().max(1))
+            .map(|t| polynomial.evaluate(&F::from(t as u64)))
+            .collect())
+    }
 
-        // Initialize the accumulator for the sum of all evaluations
-        let mut sum = F::zero();
+    /// Non-interactive round: reduces the polynomial for `target_var`, absorbs the result
+    /// into the transcript and appends the derived challenge to `randoms`
+    pub fn reduce_with_transcript(
+        &mut self,
+        target_var: usize,
+        randoms: &mut Vec<F>,
+        transcript: &mut Transcript,
+    ) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+        let polynomial = self.reduce_to_univariate(target_var, randoms)?;
+        transcript.append_round_polynomial(&polynomial);
+        randoms.push(transcript.challenge(b"round_challenge"));
+        Ok(polynomial)
+    }
 
-        // Track the number of combinations evaluated
-        let mut count = 0;
+    /// Calculates the sum of the polynomial over all possible input combinations of 0 and 1.
+    /// Panics if the combinations cannot be counted in a `usize`; see `check_num_variables`.
+    pub fn sum_over_all_inputs(&self) -> F {
+        let _span =
+            debug_span!("sum_over_all_inputs", num_variables = self.num_variables).entered();
 
         // Iterate over each combination of inputs (0s and 1s)
-        for input in combinations {
-            // Evaluate the polynomial at the current input and add it to the sum
-            let evaluation = self.polynomial.evaluate(&input);
-            // let evaluation = univariate_poly.evaluate(&input[0]);
-            sum += evaluation;
-
-            // Debugging information
-            println!(
-                "Combination {}: input = {:?}, evaluation = {:?}, sum = {:?}",
-                count, input, evaluation, sum
-            );
-
-            count += 1;
-        }
-
-        // Final debug information
-        println!(
-            "Total combinations evaluated: {}, Final sum: {:?}",
-            count, sum
+        // and add the evaluation of the polynomial at it to the sum
+        let combinations = 1 << self.num_variables;
+        let sum = fold_range(
+            combinations,
+            F::zero,
+            |sum, i| {
+                let input = Self::combination(i, self.num_variables);
+                let evaluation = self.polynomial.evaluate(&input);
+                trace!(
+                    combination = i,
+                    ?input,
+                    ?evaluation,
+                    "evaluated combination"
+                );
+                sum + evaluation
+            },
+            |a, b| a + b,
         );
 
+        debug!(combinations, ?sum, "summed over all inputs");
+
         // Return the final sum
         sum
     }
 
-    /// Generates all combinations of 0 and 1 for a given number of variables
-    fn generate_combinations(num_variables: usize) -> Vec<Vec<F>> {
-        (0..(1 << num_variables))
-            .map(|i| {
-                (0..num_variables)
-                    .map(|j| {
-                        if (i >> j) & 1 == 1 {
-                            F::one()
-                        } else {
-                            F::zero()
-                        }
-                    })
-                    .collect()
+    /// The `index`-th combination of 0 and 1 for a given number of variables:
+    /// bit `j` of `index` is the value of variable `j`
+    fn combination(index: usize, num_variables: usize) -> Vec<F> {
+        (0..num_variables)
+            .map(|j| {
+                if (index >> j) & 1 == 1 {
+                    F::one()
+                } else {
+                    F::zero()
+                }
             })
             .collect()
     }
 }
+
+/// The points of the boolean hypercube are indexed by a `usize`, which must be able to hold
+/// 2^num_variables for the Prover to enumerate them
+pub fn check_num_variables<F: Field>(num_variables: usize) -> Result<(), SumcheckError<F>> {
+    if num_variables >= usize::BITS as usize {
+        return Err(SumcheckError::InvalidParameters(format!(
+            "{} variables are too many to sum over the boolean hypercube",
+            num_variables
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,324 @@
+//! Typed sessions for the interactive protocol. Each side moves through the states
+//! `AwaitingClaim` → `Round` → `Finished`, and every transition consumes the session, so
+//! that messages can only be sent and received in protocol order: there is no way to send
+//! a round polynomial before the claim, to reuse a round, or to keep going once finished.
+//! What the types cannot see, such as a message tagged with the wrong round, is an error.
+//!
+//! These sessions are the misuse-safe way to run the protocol. `Verifier` and `Prover`
+//! stay public for the proof, batching and GKR layers built on them, but their public
+//! fields and round methods, such as `Verifier::challenge_values`,
+//! `Verifier::current_claim` and `Prover::reduce_to_univariate`, leave the ordering of
+//! the protocol to the caller.
+
+use crate::challenge::{ChallengeSource, OsChallenges};
+use crate::error::SumcheckError;
+use crate::polynomial::variable_degrees;
+use crate::prover::Prover;
+use crate::verifier::{EvaluationOracle, Subclaim, Verifier};
+use ark_ff::Field;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm},
+    univariate::SparsePolynomial as UniSparsePolynomial,
+};
+use std::marker::PhantomData;
+
+/// The claimed sum has not been exchanged yet
+#[derive(Debug)]
+pub struct AwaitingClaim;
+
+/// In the middle of a round: its number is `round()` on the session
+#[derive(Debug)]
+pub struct Round;
+
+/// Every round has been played
+#[derive(Debug)]
+pub struct Finished;
+
+/// The Prover's side of one run of the protocol
+pub struct ProverSession<F: Field, S> {
+    prover: Prover<F>,
+    challenges: Vec<F>, // The Verifier's challenges received so far
+    _state: PhantomData<S>,
+}
+
+/// Where a `ProverSession` goes once the claim is sent or a challenge received
+pub enum ProverStep<F: Field> {
+    Round(ProverSession<F, Round>),
+    Finished(ProverSession<F, Finished>),
+}
+
+impl<F: Field, S> ProverSession<F, S> {
+    /// The challenges received so far
+    pub fn challenges(&self) -> &[F] {
+        &self.challenges
+    }
+
+    fn into_state<T>(self) -> ProverSession<F, T> {
+        ProverSession {
+            prover: self.prover,
+            challenges: self.challenges,
+            _state: PhantomData,
+        }
+    }
+
+    /// The next round, or the end of the protocol once every variable has a challenge
+    fn step(self) -> ProverStep<F> {
+        if self.challenges.len() < self.prover.num_variables {
+            ProverStep::Round(self.into_state())
+        } else {
+            ProverStep::Finished(self.into_state())
+        }
+    }
+}
+
+impl<F: Field> ProverSession<F, AwaitingClaim> {
+    /// Starts a session proving the sum of `polynomial` over the boolean hypercube
+    pub fn new(polynomial: SparsePolynomial<F, SparseTerm>) -> Self {
+        ProverSession {
+            prover: Prover::new_with_polynomial(polynomial),
+            challenges: Vec::new(),
+            _state: PhantomData,
+        }
+    }
+
+    /// Sends the claimed sum and moves to the first round
+    pub fn send_claim(self) -> (F, ProverStep<F>) {
+        let claimed_sum = self.prover.sum_over_all_inputs();
+        (claimed_sum, self.step())
+    }
+}
+
+impl<F: Field> ProverSession<F, Round> {
+    /// The current round, which is also the variable being reduced
+    pub fn round(&self) -> usize {
+        self.challenges.len()
+    }
+
+    /// The round polynomial of the current round, with the previous variables fixed to the
+    /// challenges received so far
+    pub fn round_polynomial(&mut self) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+        let round = self.round();
+        self.prover.reduce_to_univariate(round, &self.challenges)
+    }
+
+    /// Receives the challenge of the current round and moves to the next one
+    pub fn receive_challenge(mut self, challenge: F) -> ProverStep<F> {
+        self.challenges.push(challenge);
+        self.step()
+    }
+}
+
+/// The Verifier's side of one run of the protocol
+pub struct VerifierSession<F: Field, S, C: ChallengeSource<F> = OsChallenges> {
+    verifier: Verifier<F, C>,
+    _state: PhantomData<S>,
+}
+
+/// Where a `VerifierSession` goes once the claim or a round polynomial is accepted
+pub enum VerifierStep<F: Field, C: ChallengeSource<F> = OsChallenges> {
+    Round(VerifierSession<F, Round, C>),
+    Finished(VerifierSession<F, Finished, C>),
+}
+
+impl<F: Field, S, C: ChallengeSource<F>> VerifierSession<F, S, C> {
+    /// The challenges sent so far; the last one is the answer to the latest round
+    pub fn challenges(&self) -> &[F] {
+        &self.verifier.challenge_values
+    }
+
+    fn into_state<T>(self) -> VerifierSession<F, T, C> {
+        VerifierSession {
+            verifier: self.verifier,
+            _state: PhantomData,
+        }
+    }
+
+    fn step(self) -> VerifierStep<F, C> {
+        if self.verifier.challenge_values.len() < self.verifier.num_variables {
+            VerifierStep::Round(self.into_state())
+        } else {
+            VerifierStep::Finished(self.into_state())
+        }
+    }
+}
+
+impl<F: Field> VerifierSession<F, AwaitingClaim> {
+    /// Starts a session checking round polynomials against `degree_bounds`,
+    /// with fresh random challenges
+    pub fn new(degree_bounds: Vec<usize>) -> Self {
+        Self::with_challenge_source(degree_bounds, OsChallenges)
+    }
+
+    /// Starts a session with degree bounds taken from the polynomial being checked
+    pub fn from_polynomial(polynomial: &SparsePolynomial<F, SparseTerm>) -> Self {
+        Self::new(variable_degrees(polynomial))
+    }
+}
+
+impl<F: Field, C: ChallengeSource<F>> VerifierSession<F, AwaitingClaim, C> {
+    /// Starts a session whose challenges come from `challenge_source`
+    pub fn with_challenge_source(degree_bounds: Vec<usize>, challenge_source: C) -> Self {
+        VerifierSession {
+            verifier: Verifier::new(F::zero(), degree_bounds)
+                .with_challenge_source(challenge_source),
+            _state: PhantomData,
+        }
+    }
+
+    /// Receives the claimed sum and moves to the first round
+    pub fn receive_claim(mut self, claimed_sum: F) -> VerifierStep<F, C> {
+        self.verifier.expected_sum = claimed_sum;
+        self.verifier.current_claim = claimed_sum;
+        self.step()
+    }
+}
+
+impl<F: Field, C: ChallengeSource<F>> VerifierSession<F, Round, C> {
+    /// The round whose polynomial is expected next
+    pub fn round(&self) -> usize {
+        self.verifier.challenge_values.len()
+    }
+
+    /// Receives the polynomial the Prover sent for `round`. It is checked against the
+    /// current claim; once accepted, the round's challenge is chosen and the session moves
+    /// on. A rejected polynomial, or one tagged for another round, ends the session.
+    pub fn receive_polynomial(
+        mut self,
+        round: usize,
+        polynomial: &UniSparsePolynomial<F>,
+    ) -> Result<VerifierStep<F, C>, SumcheckError<F>> {
+        if round != self.round() {
+            return Err(SumcheckError::WrongRoundCount {
+                expected: self.round(),
+                got: round,
+            });
+        }
+        let claim = self.verifier.current_claim;
+        self.verifier
+            .verify_and_challenge(polynomial, round, &claim)?;
+        Ok(self.step())
+    }
+}
+
+impl<F: Field, C: ChallengeSource<F>> VerifierSession<F, Finished, C> {
+    /// The subclaim left once every round has passed
+    pub fn subclaim(&self) -> Subclaim<F> {
+        self.verifier
+            .finalize()
+            .expect("a finished session has a challenge for every variable")
+    }
+
+    /// Ends the protocol with the final check against the polynomial oracle
+    pub fn finalize_with_oracle<O: EvaluationOracle<F> + ?Sized>(
+        self,
+        oracle: &O,
+    ) -> Result<Subclaim<F>, SumcheckError<F>> {
+        self.verifier.finalize_with_oracle(oracle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::ScriptedChallenges;
+    use crate::parser::parse_polynomial;
+    use ark_test_curves::fp128::Fq;
+
+    fn sample_polynomial() -> SparsePolynomial<Fq, SparseTerm> {
+        parse_polynomial("3*x0^3*x1 + 3*x0*x2 + 2*x1*x2 + 1").unwrap()
+    }
+
+    /// Plays an honest run through both sessions and returns the Verifier's final state
+    fn run(
+        polynomial: &SparsePolynomial<Fq, SparseTerm>,
+    ) -> Result<VerifierSession<Fq, Finished>, SumcheckError<Fq>> {
+        let (claimed_sum, mut prover) = ProverSession::new(polynomial.clone()).send_claim();
+        let mut verifier = VerifierSession::from_polynomial(polynomial).receive_claim(claimed_sum);
+        loop {
+            let (mut prover_round, verifier_round) = match (prover, verifier) {
+                (ProverStep::Round(p), VerifierStep::Round(v)) => (p, v),
+                (ProverStep::Finished(_), VerifierStep::Finished(v)) => return Ok(v),
+                _ => panic!("the sessions disagree on the number of rounds"),
+            };
+            let round_polynomial = prover_round.round_polynomial()?;
+            let round = prover_round.round();
+            verifier = verifier_round.receive_polynomial(round, &round_polynomial)?;
+            let challenge = *verifier_challenges(&verifier).last().unwrap();
+            prover = prover_round.receive_challenge(challenge);
+        }
+    }
+
+    fn verifier_challenges<C: ChallengeSource<Fq>>(step: &VerifierStep<Fq, C>) -> &[Fq] {
+        match step {
+            VerifierStep::Round(session) => session.challenges(),
+            VerifierStep::Finished(session) => session.challenges(),
+        }
+    }
+
+    #[test]
+    fn test_sessions_run_honest_protocol() {
+        let poly = sample_polynomial();
+        let finished = run(&poly).unwrap();
+        assert_eq!(finished.challenges().len(), 3);
+        assert_eq!(finished.subclaim().check(&poly), Ok(()));
+        assert!(finished.finalize_with_oracle(&poly).is_ok());
+
+        // Without variables, the claim is all there is
+        let constant = parse_polynomial::<Fq>("5").unwrap();
+        let finished = run(&constant).unwrap();
+        assert_eq!(finished.subclaim().expected_value, Fq::from(5));
+    }
+
+    #[test]
+    fn test_verifier_session_refuses_out_of_order_messages() {
+        let poly = sample_polynomial();
+        let (claimed_sum, prover) = ProverSession::new(poly.clone()).send_claim();
+        let ProverStep::Round(mut prover) = prover else {
+            panic!("three rounds expected")
+        };
+        let round_polynomial = prover.round_polynomial().unwrap();
+
+        let verifier = VerifierSession::with_challenge_source(
+            variable_degrees(&poly),
+            ScriptedChallenges::new(vec![Fq::from(4), Fq::from(9), Fq::from(2)]),
+        );
+        let VerifierStep::Round(verifier) = verifier.receive_claim(claimed_sum) else {
+            panic!("three rounds expected")
+        };
+
+        // A message tagged for a later round is refused
+        assert_eq!(
+            verifier
+                .receive_polynomial(1, &round_polynomial)
+                .err()
+                .unwrap(),
+            SumcheckError::WrongRoundCount {
+                expected: 0,
+                got: 1
+            }
+        );
+
+        // Replaying the round 0 polynomial in round 1 exceeds the degree bound of x1
+        let verifier = VerifierSession::with_challenge_source(
+            variable_degrees(&poly),
+            ScriptedChallenges::new(vec![Fq::from(4), Fq::from(9), Fq::from(2)]),
+        );
+        let VerifierStep::Round(verifier) = verifier.receive_claim(claimed_sum) else {
+            panic!("three rounds expected")
+        };
+        let VerifierStep::Round(verifier) =
+            verifier.receive_polynomial(0, &round_polynomial).unwrap()
+        else {
+            panic!("three rounds expected")
+        };
+        assert_eq!(verifier.challenges(), &[Fq::from(4)]);
+        assert!(matches!(
+            verifier.receive_polynomial(1, &round_polynomial),
+            Err(SumcheckError::DegreeTooHigh {
+                round: 1,
+                degree: 3,
+                bound: 1
+            })
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+//! Empirical soundness error. A cheating Prover convinces the Verifier with probability at
+//! most v * d / |F| for v variables of degree at most d; over a small field this is large
+//! enough to be measured by running the protocol many times with seeded challenges.
+
+use crate::challenge::SeededChallenges;
+use crate::cheating::{CheatingProver, CheatingStrategy};
+use crate::error::SumcheckError;
+use crate::polynomial::variable_degrees;
+use crate::verifier::Verifier;
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::multivariate::{SparsePolynomial, SparseTerm};
+use std::fmt;
+use tracing::debug_span;
+
+/// Outcome of `measure_soundness`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundnessReport {
+    pub trials: usize,
+    pub accepted: usize,
+    pub num_variables: usize,
+    pub max_degree: usize,
+    pub field_size: f64,
+}
+
+impl SoundnessReport {
+    /// Fraction of the runs in which the Verifier accepted
+    pub fn acceptance_rate(&self) -> f64 {
+        self.accepted as f64 / self.trials as f64
+    }
+
+    /// The soundness error of the protocol, v * d / |F|
+    pub fn theoretical_bound(&self) -> f64 {
+        (self.num_variables * self.max_degree) as f64 / self.field_size
+    }
+}
+
+impl fmt::Display for SoundnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "accepted {} of {} runs ({:.4}), bound v * d / |F| = {} * {} / {} = {:.4}",
+            self.accepted,
+            self.trials,
+            self.acceptance_rate(),
+            self.num_variables,
+            self.max_degree,
+            self.field_size,
+            self.theoretical_bound()
+        )
+    }
+}
+
+/// Size of the field as a float; exact for the small fields this is meant for
+fn field_size<F: PrimeField>() -> f64 {
+    F::MODULUS
+        .to_bytes_le()
+        .iter()
+        .rev()
+        .fold(0.0, |size, byte| size * 256.0 + *byte as f64)
+}
+
+/// Runs the interactive protocol `trials` times between a `Verifier` and a `CheatingProver`
+/// following `strategy`, with every challenge drawn from `SeededChallenges` seeded with
+/// `seed`, and counts the runs in which the Verifier accepts, final evaluation check included
+pub fn measure_soundness<F: PrimeField>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+    strategy: CheatingStrategy<F>,
+    trials: usize,
+    seed: u64,
+) -> Result<SoundnessReport, SumcheckError<F>> {
+    let _span = debug_span!("measure_soundness", trials, seed).entered();
+    let mut challenges = SeededChallenges::new(seed);
+
+    let mut accepted = 0;
+    for _ in 0..trials {
+        let mut prover = CheatingProver::new(polynomial.clone(), strategy);
+        let mut verifier = Verifier::from_polynomial(polynomial, prover.claimed_sum())
+            .with_challenge_source(&mut challenges);
+
+        let mut rejected = false;
+        for i in 0..polynomial.num_vars {
+            // An error here is a failure of the cheating Prover, not a rejection
+            let round_polynomial = prover.reduce_to_univariate(i, &verifier.challenge_values)?;
+            let claim = verifier.current_claim;
+            if verifier
+                .verify_and_challenge(&round_polynomial, i, &claim)
+                .is_err()
+            {
+                rejected = true;
+                break;
+            }
+        }
+        if !rejected && verifier.finalize_with_oracle(polynomial).is_ok() {
+            accepted += 1;
+        }
+    }
+
+    Ok(SoundnessReport {
+        trials,
+        accepted,
+        num_variables: polynomial.num_vars,
+        max_degree: variable_degrees(polynomial).into_iter().max().unwrap_or(0),
+        field_size: field_size::<F>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_polynomial;
+    use ark_ff::fields::{Fp64, MontBackend};
+
+    /// The prime field with 101 elements
+    #[derive(ark_ff::MontConfig)]
+    #[modulus = "101"]
+    #[generator = "2"]
+    struct F101Config;
+    type F101 = Fp64<MontBackend<F101Config, 1>>;
+
+    #[test]
+    fn test_adaptive_prover_stays_within_bound() {
+        let poly = parse_polynomial::<F101>("x0^2*x1 + 3*x1^2*x2 + x2^2 + 4").unwrap();
+        let report = measure_soundness(
+            &poly,
+            CheatingStrategy::Adaptive {
+                offset: F101::from(1),
+            },
+            5000,
+            42,
+        )
+        .unwrap();
+
+        assert_eq!(report.field_size, 101.0);
+        assert_eq!((report.num_variables, report.max_degree), (3, 2));
+        // Expected rate 1 - (1 - 2/101)^3, about 0.058, against a bound of 6/101
+        assert!(report.accepted > 0, "{}", report);
+        assert!(
+            report.acceptance_rate() < report.theoretical_bound() * 1.2,
+            "{}",
+            report
+        );
+    }
+
+    #[test]
+    fn test_rejected_strategies_are_never_accepted() {
+        let poly = parse_polynomial::<F101>("x0^2*x1 + 3*x1^2*x2 + x2^2 + 4").unwrap();
+        for strategy in [
+            CheatingStrategy::WrongSum {
+                offset: F101::from(1),
+            },
+            CheatingStrategy::DegreeTooHigh { round: 1 },
+        ] {
+            let report = measure_soundness(&poly, strategy, 200, 7).unwrap();
+            assert_eq!(report.accepted, 0, "{}", report);
+        }
+    }
+}
@@ -1,16 +1,26 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::{
-        polynomial::max_variables, prover::Prover, verifier::Verifier, MAX_DEGREE,
-        MAX_NUM_VARIABLES, MAX_TERMS,
+        error::SumcheckError, mle::mle_from_evaluations, multilinear::MultilinearProver,
+        parser::parse_polynomial, polynomial::max_variables, proof, prover::Prover,
+        transcript::Transcript, verifier::Verifier, MAX_DEGREE, MAX_NUM_VARIABLES, MAX_TERMS,
     };
     use ark_poly::{
         multivariate::{SparsePolynomial, SparseTerm, Term},
-        // univariate::SparsePolynomial as UniSparsePolynomial,
-        DenseMVPolynomial,
-        Polynomial,
+        univariate::SparsePolynomial as UniSparsePolynomial,
+        DenseMVPolynomial, Polynomial,
     };
     use ark_test_curves::fp128::Fq;
+    use rand::{thread_rng, Rng};
+
+    /// Draws a fresh seed for the random polynomial and prints it;
+    /// a failing run is replayed by passing the printed seed to `Prover::new_seeded`
+    fn fresh_seed() -> u64 {
+        let seed = thread_rng().gen();
+        println!("Polynomial seed: {}", seed);
+        seed
+    }
 
     #[test]
     fn test_sumcheck_protocol() {
@@ -19,7 +29,9 @@ mod tests {
         let max_terms: usize = MAX_TERMS;
 
         // Step 1: Prover generates a polynomial
-        let mut prover = Prover::<Fq>::new(max_num_variables, max_degree, max_terms);
+        let seed = fresh_seed();
+        let mut prover = Prover::<Fq>::new_seeded(seed, max_num_variables, max_degree, max_terms)
+            .expect("Valid generation parameters");
         println!("Generated Polynomial: {:?}", prover.polynomial);
 
         // Step 2: Prover calculates the sum over all inputs
@@ -29,27 +41,30 @@ mod tests {
         let num_variables = prover.num_variables;
 
         // Step 3: Verifier sets the expected sum
-        let mut verifier = Verifier::new(num_variables, sum);
+        let mut verifier = Verifier::from_polynomial(&prover.polynomial, sum);
         let mut eval = sum;
 
         // Step 4: Perform Sumcheck protocol rounds
         for i in 0..num_variables {
             println!("challenge: {:?}", verifier.challenge_values);
-            let i_poly = prover.reduce_to_univariate(i, &verifier.challenge_values);
-            if let Some(current_eval) = verifier.verify_and_challenge(&i_poly, i, &eval) {
-                println!("Round {} succeeded", i + 1);
-                // println!("challenge: {:?}", verifier.challenge_values);
-                // sum = ith_poly.evaluate(&verifier.challenge_values[i])
-                eval = current_eval;
-            } else {
-                assert!(false, "Verification failed at round {}", i);
+            let i_poly = prover
+                .reduce_to_univariate(i, &verifier.challenge_values)
+                .expect("Round of an honest prover");
+            match verifier.verify_and_challenge(&i_poly, i, &eval) {
+                Ok(current_eval) => {
+                    println!("Round {} succeeded", i + 1);
+                    eval = current_eval;
+                }
+                Err(err) => panic!(
+                    "Verification failed at round {} (seed {}): {}",
+                    i, seed, err
+                ),
             }
         }
-        let init_poly = prover.polynomial;
-        assert!(
-            init_poly.evaluate(&verifier.challenge_values) == eval,
-            "Initial evaluated value does not match the sum."
-        );
+        let subclaim = verifier
+            .finalize_with_oracle(&prover.polynomial)
+            .unwrap_or_else(|err| panic!("Final check failed (seed {}): {}", seed, err));
+        assert_eq!(subclaim.expected_value, eval);
     }
 
     #[test]
@@ -78,26 +93,419 @@ mod tests {
         println!("Prover calculated sum: {:?}", sum);
 
         // Step 3: Verifier sets the expected sum
-        let mut verifier = Verifier::new(num_variables, sum);
+        let mut verifier = Verifier::from_polynomial(&prover.polynomial, sum);
         let mut eval = sum;
 
         // Step 4: Perform Sumcheck protocol rounds
         for i in 0..num_variables {
             println!("challenge: {:?}", verifier.challenge_values);
-            let i_poly = prover.reduce_to_univariate(i, &verifier.challenge_values);
-            if let Some(current_eval) = verifier.verify_and_challenge(&i_poly, i, &eval) {
-                println!("Round {} succeeded", i + 1);
-                // println!("challenge: {:?}", verifier.challenge_values);
-                // sum = ith_poly.evaluate(&verifier.challenge_values[i])
-                eval = current_eval;
-            } else {
-                assert!(false, "Verification failed at round {}", i);
+            let i_poly = prover
+                .reduce_to_univariate(i, &verifier.challenge_values)
+                .expect("Round of an honest prover");
+            match verifier.verify_and_challenge(&i_poly, i, &eval) {
+                Ok(current_eval) => {
+                    println!("Round {} succeeded", i + 1);
+                    eval = current_eval;
+                }
+                Err(err) => panic!("Verification failed at round {}: {}", i, err),
+            }
+        }
+        let subclaim = verifier
+            .finalize_with_oracle(&prover.polynomial)
+            .expect("Initial evaluated value does not match the sum.");
+        assert_eq!(subclaim.expected_value, eval);
+    }
+
+    #[test]
+    fn test_non_interactive_sumcheck_protocol() {
+        let seed = fresh_seed();
+        let mut prover = Prover::<Fq>::new_seeded(seed, MAX_NUM_VARIABLES, MAX_DEGREE, MAX_TERMS)
+            .expect("Valid generation parameters");
+        let num_variables = prover.num_variables;
+        let max_degree = prover.polynomial.degree();
+
+        // Prover side: derive every challenge from its own transcript
+        let sum = prover.sum_over_all_inputs();
+        let mut prover_transcript = Transcript::new(b"sumcheck");
+        prover_transcript.append_claimed_sum(&sum);
+        prover_transcript.append_polynomial_info(num_variables, max_degree);
+        prover_transcript.append_polynomial(&prover.polynomial);
+
+        let mut prover_challenges = Vec::new();
+        let mut round_polynomials = Vec::new();
+        for i in 0..num_variables {
+            round_polynomials.push(
+                prover
+                    .reduce_with_transcript(i, &mut prover_challenges, &mut prover_transcript)
+                    .expect("Round of an honest prover"),
+            );
+        }
+
+        // Verifier side: replay the transcript from the proof alone
+        let mut verifier_transcript = Transcript::new(b"sumcheck");
+        verifier_transcript.append_claimed_sum(&sum);
+        verifier_transcript.append_polynomial_info(num_variables, max_degree);
+        verifier_transcript.append_polynomial(&prover.polynomial);
+
+        let mut verifier = Verifier::from_polynomial(&prover.polynomial, sum)
+            .with_challenge_source(verifier_transcript);
+        let mut eval = sum;
+        for (i, i_poly) in round_polynomials.iter().enumerate() {
+            match verifier.verify_and_challenge(i_poly, i, &eval) {
+                Ok(current_eval) => eval = current_eval,
+                Err(err) => panic!(
+                    "Verification failed at round {} (seed {}): {}",
+                    i, seed, err
+                ),
             }
         }
-        let init_poly = prover.polynomial;
+
+        assert_eq!(
+            verifier.challenge_values, prover_challenges,
+            "Prover and Verifier should derive the same challenges"
+        );
+        let subclaim = verifier
+            .finalize_with_oracle(&prover.polynomial)
+            .expect("Initial evaluated value does not match the sum.");
+        assert_eq!(subclaim.expected_value, eval);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_transcript_rounds_match_challenge_source() {
+        let poly = parse_polynomial::<Fq>("3*x0^3*x1 + 3*x0*x2 + 2*x1*x2^2 + 5").unwrap();
+        let mut prover = Prover::new_with_polynomial(poly.clone());
+        let sum = prover.sum_over_all_inputs();
+
+        let mut source_verifier =
+            Verifier::from_polynomial(&poly, sum).with_challenge_source(Transcript::new(b"test"));
+        let mut verifier = Verifier::from_polynomial(&poly, sum);
+        let mut transcript = Transcript::new(b"test");
+        for i in 0..poly.num_vars {
+            let i_poly = prover
+                .reduce_to_univariate(i, &verifier.challenge_values)
+                .expect("Round of an honest prover");
+            let claim = verifier.current_claim;
+            let expected = source_verifier.verify_and_challenge(&i_poly, i, &claim);
+            assert_eq!(
+                verifier.verify_and_challenge_with_transcript(&i_poly, i, &claim, &mut transcript),
+                expected
+            );
+        }
+        assert_eq!(verifier.finalize(), source_verifier.finalize());
+    }
+
+    #[test]
+    fn test_prove_and_verify_random_polynomial() {
+        let seed = fresh_seed();
+        let prover = Prover::<Fq>::new_seeded(seed, MAX_NUM_VARIABLES, MAX_DEGREE, MAX_TERMS)
+            .expect("Valid generation parameters");
+        let polynomial = prover.polynomial;
+
+        let (proof, challenges) = proof::prove(&polynomial).expect("Honest proof");
+        let subclaim = proof::verify(&proof, &polynomial)
+            .unwrap_or_else(|err| panic!("Honest proof should verify (seed {}): {}", seed, err));
+
+        assert_eq!(subclaim.point, challenges);
+        subclaim
+            .check(&polynomial)
+            .expect("Initial evaluated value does not match the sum.");
+    }
+
+    #[test]
+    fn test_verifier_rejects_round_polynomial_above_degree_bound() {
+        // x0 * x1 has degree 1 in every variable
+        let poly = SparsePolynomial::from_coefficients_vec(
+            2,
+            vec![(Fq::from(1), SparseTerm::new(vec![(0, 1), (1, 1)]))],
+        );
+        let prover = Prover::<Fq>::new_with_polynomial(poly);
+        let sum = prover.sum_over_all_inputs();
+        let mut verifier = Verifier::from_polynomial(&prover.polynomial, sum);
+
+        // g(X) = X^2 passes the sum check (0 + 1 = 1) but exceeds the degree bound
+        let cheating_poly = UniSparsePolynomial::from_coefficients_vec(vec![(2, Fq::from(1))]);
+        assert_eq!(
+            verifier.verify_and_challenge(&cheating_poly, 0, &sum),
+            Err(SumcheckError::DegreeTooHigh {
+                round: 0,
+                degree: 2,
+                bound: 1
+            }),
+            "Round polynomial above the degree bound must be rejected"
+        );
+    }
+
+    #[test]
+    fn test_verifier_rejects_oversized_evaluations_before_interpolating() {
+        let mut verifier = Verifier::new(Fq::from(0), vec![2, 2]);
+
+        // Rejected on its length alone: nothing is interpolated and no challenge is drawn
+        let evaluations = vec![Fq::from(1); 1 << 20];
+        assert_eq!(
+            verifier.verify_evaluations_and_challenge(&evaluations, 0, &Fq::from(0)),
+            Err(SumcheckError::DegreeTooHigh {
+                round: 0,
+                degree: (1 << 20) - 1,
+                bound: 2
+            })
+        );
+        assert!(verifier.challenge_values.is_empty());
+    }
+
+    #[test]
+    fn test_verifier_rejects_missing_evaluations() {
+        let mut verifier = Verifier::new(Fq::from(0), vec![2]);
+
+        // An empty message is not the zero polynomial, and g(1) cannot be left out
+        for evaluations in [vec![], vec![Fq::from(0)]] {
+            assert!(matches!(
+                verifier.verify_evaluations_and_challenge(&evaluations, 0, &Fq::from(0)),
+                Err(SumcheckError::MalformedEncoding(_))
+            ));
+        }
+        assert!(verifier.challenge_values.is_empty());
+
+        assert_eq!(
+            verifier.verify_evaluations_and_challenge(&[Fq::from(0), Fq::from(0)], 0, &Fq::from(0)),
+            Ok(Fq::from(0))
+        );
+    }
+
+    #[test]
+    fn test_finalize_rejects_incomplete_protocol_and_wrong_oracle() {
+        let seed = fresh_seed();
+        let mut prover = Prover::<Fq>::new_seeded(seed, MAX_NUM_VARIABLES, MAX_DEGREE, MAX_TERMS)
+            .expect("Valid generation parameters");
+        let sum = prover.sum_over_all_inputs();
+        let mut verifier = Verifier::from_polynomial(&prover.polynomial, sum);
+
+        // Finalizing before all rounds are done must fail
+        assert!(
+            verifier.finalize().is_err(),
+            "Verifier must not finalize before every round has passed"
+        );
+
+        let mut eval = sum;
+        for i in 0..prover.num_variables {
+            let i_poly = prover
+                .reduce_to_univariate(i, &verifier.challenge_values)
+                .expect("Round of an honest prover");
+            eval = verifier
+                .verify_and_challenge(&i_poly, i, &eval)
+                .expect("Honest round should verify");
+        }
+
+        // An oracle for a different polynomial must be rejected in the final check
+        let wrong_oracle = |point: &[Fq]| prover.polynomial.evaluate(&point.to_vec()) + Fq::from(1);
         assert!(
-            init_poly.evaluate(&verifier.challenge_values) == eval,
-            "Initial evaluated value does not match the sum."
+            verifier.finalize_with_oracle(&wrong_oracle).is_err(),
+            "Final check must reject an oracle that disagrees with the last claim"
+        );
+        assert!(verifier.finalize_with_oracle(&prover.polynomial).is_ok());
+    }
+
+    #[test]
+    fn test_out_of_order_calls_return_errors() {
+        let poly = SparsePolynomial::from_coefficients_vec(
+            2,
+            vec![(Fq::from(1), SparseTerm::new(vec![(0, 1), (1, 1)]))],
+        );
+        let mut prover = Prover::<Fq>::new_with_polynomial(poly);
+        let sum = prover.sum_over_all_inputs();
+        let mut verifier = Verifier::from_polynomial(&prover.polynomial, sum);
+
+        assert_eq!(
+            prover.reduce_to_univariate(2, &vec![Fq::from(1), Fq::from(2)]),
+            Err(SumcheckError::VariableOutOfRange {
+                variable: 2,
+                num_variables: 2
+            })
+        );
+        assert_eq!(
+            prover.reduce_to_univariate(1, &vec![]),
+            Err(SumcheckError::WrongRoundCount {
+                expected: 1,
+                got: 0
+            })
+        );
+
+        let first_poly = prover.reduce_to_univariate(0, &vec![]).unwrap();
+        assert_eq!(
+            verifier.verify_and_challenge(&first_poly, 1, &sum),
+            Err(SumcheckError::WrongRoundCount {
+                expected: 0,
+                got: 1
+            })
         );
     }
+
+    #[test]
+    fn test_sumcheck_protocol_with_dense_mle() {
+        let evaluations: Vec<Fq> = (0..16u64).map(|i| Fq::from(3 * i + 1)).collect();
+        let mle = mle_from_evaluations(evaluations).expect("Power-of-two table");
+
+        // The sparse Prover and the folding Prover accept the same dense input
+        let mut prover = Prover::new_with_mle(&mle);
+        let mut multilinear_prover = MultilinearProver::from_mle(mle);
+        let sum = prover.sum_over_all_inputs();
+        assert_eq!(sum, multilinear_prover.sum_over_all_inputs());
+
+        let mut verifier = Verifier::from_polynomial(&prover.polynomial, sum);
+        let mut eval = sum;
+        for i in 0..prover.num_variables {
+            let i_poly = prover
+                .reduce_to_univariate(i, &verifier.challenge_values)
+                .expect("Round of an honest prover");
+            assert_eq!(
+                i_poly,
+                multilinear_prover
+                    .reduce_to_univariate(i, &verifier.challenge_values)
+                    .expect("Round of an honest prover")
+            );
+            eval = verifier
+                .verify_and_challenge(&i_poly, i, &eval)
+                .expect("Honest round should verify");
+        }
+        verifier
+            .finalize_with_oracle(&prover.polynomial)
+            .expect("Initial evaluated value does not match the sum.");
+    }
+
+    #[test]
+    fn test_seeded_prover_is_reproducible() {
+        let seed = fresh_seed();
+        let prover1 = Prover::<Fq>::new_seeded(seed, MAX_NUM_VARIABLES, MAX_DEGREE, MAX_TERMS)
+            .expect("Valid generation parameters");
+        let prover2 = Prover::<Fq>::new_seeded(seed, MAX_NUM_VARIABLES, MAX_DEGREE, MAX_TERMS)
+            .expect("Valid generation parameters");
+
+        assert_eq!(prover1.polynomial, prover2.polynomial);
+        assert_eq!(prover1.num_variables, prover2.num_variables);
+    }
+}
+
+/// Property tests over arbitrary polynomials. Failing cases are shrunk by proptest
+/// towards fewer variables, fewer terms and smaller coefficients and degrees.
+#[cfg(test)]
+mod properties {
+    use crate::{polynomial::variable_degrees, proof, prover::Prover, verifier::Verifier};
+    use ark_poly::{
+        multivariate::{SparsePolynomial, SparseTerm, Term},
+        DenseMVPolynomial, Polynomial,
+    };
+    use ark_test_curves::fp128::Fq;
+    use proptest::prelude::*;
+
+    const MAX_VARIABLES: usize = 4;
+    const MAX_DEGREE: usize = 3;
+
+    /// A polynomial in 1..=MAX_VARIABLES variables, as a list of (coefficient, degrees)
+    /// with one degree in 0..=MAX_DEGREE per variable
+    fn arbitrary_polynomial() -> impl Strategy<Value = SparsePolynomial<Fq, SparseTerm>> {
+        (1..=MAX_VARIABLES).prop_flat_map(|num_variables| {
+            prop::collection::vec(
+                (
+                    any::<u64>(),
+                    prop::collection::vec(0..=MAX_DEGREE, num_variables),
+                ),
+                1..8,
+            )
+            .prop_map(move |terms| {
+                let terms = terms
+                    .into_iter()
+                    .map(|(coeff, degrees)| {
+                        let powers = degrees
+                            .into_iter()
+                            .enumerate()
+                            .filter(|(_, degree)| *degree > 0)
+                            .collect();
+                        (Fq::from(coeff), SparseTerm::new(powers))
+                    })
+                    .collect();
+                SparsePolynomial::from_coefficients_vec(num_variables, terms)
+            })
+        })
+    }
+
+    /// Reference sum over the hypercube: a term is 1 at a boolean point exactly when
+    /// every one of its variables is 1, and 0 otherwise
+    fn brute_force_sum(poly: &SparsePolynomial<Fq, SparseTerm>) -> Fq {
+        let mut sum = Fq::from(0);
+        for point in 0..1usize << poly.num_vars {
+            for (coeff, term) in &poly.terms {
+                if term.iter().all(|(var, _)| (point >> var) & 1 == 1) {
+                    sum += coeff;
+                }
+            }
+        }
+        sum
+    }
+
+    /// g_i(t): the polynomial with its first variables fixed to `challenges`, the next one
+    /// to t, summed over the boolean values of the others
+    fn partial_sum(poly: &SparsePolynomial<Fq, SparseTerm>, challenges: &[Fq], t: Fq) -> Fq {
+        let remaining = poly.num_vars - challenges.len() - 1;
+        (0..1usize << remaining)
+            .map(|suffix| {
+                let mut point = challenges.to_vec();
+                point.push(t);
+                point.extend((0..remaining).map(|j| Fq::from(((suffix >> j) & 1) as u64)));
+                poly.evaluate(&point)
+            })
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn honest_run_always_verifies(poly in arbitrary_polynomial()) {
+            let mut prover = Prover::new_with_polynomial(poly.clone());
+            let sum = prover.sum_over_all_inputs();
+            let mut verifier = Verifier::from_polynomial(&poly, sum);
+            for i in 0..poly.num_vars {
+                let round_polynomial = prover.reduce_to_univariate(i, &verifier.challenge_values);
+                prop_assert!(round_polynomial.is_ok());
+                let claim = verifier.current_claim;
+                let verdict = verifier.verify_and_challenge(&round_polynomial.unwrap(), i, &claim);
+                prop_assert!(verdict.is_ok(), "round {} rejected: {:?}", i, verdict);
+            }
+            prop_assert!(verifier.finalize_with_oracle(&poly).is_ok());
+
+            let (proof, _) = proof::prove(&poly).unwrap();
+            let subclaim = proof::verify(&proof, &poly);
+            prop_assert!(subclaim.is_ok(), "proof rejected: {:?}", subclaim);
+            prop_assert_eq!(subclaim.unwrap().check(&poly), Ok(()));
+        }
+
+        #[test]
+        fn sum_over_all_inputs_matches_brute_force(poly in arbitrary_polynomial()) {
+            let prover = Prover::new_with_polynomial(poly.clone());
+            prop_assert_eq!(prover.sum_over_all_inputs(), brute_force_sum(&poly));
+        }
+
+        #[test]
+        fn reduce_to_univariate_matches_partial_evaluation(
+            poly in arbitrary_polynomial(),
+            challenges in prop::collection::vec(any::<u64>(), MAX_VARIABLES),
+            t in any::<u64>(),
+        ) {
+            let challenges: Vec<Fq> = challenges.into_iter().map(Fq::from).collect();
+            let degrees = variable_degrees(&poly);
+            let mut prover = Prover::new_with_polynomial(poly.clone());
+            for i in 0..poly.num_vars {
+                let randoms = challenges[..i].to_vec();
+                let round_polynomial = prover.reduce_to_univariate(i, &randoms).unwrap();
+                prop_assert!(round_polynomial.degree() <= degrees[i]);
+                // Agreeing on degrees[i] + 1 points and a random one pins the polynomial down
+                let points = (0..=degrees[i] as u64).map(Fq::from).chain([Fq::from(t)]);
+                for point in points {
+                    prop_assert_eq!(
+                        round_polynomial.evaluate(&point),
+                        partial_sum(&poly, &randoms, point),
+                        "round {} at {}", i, point
+                    );
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,225 @@
+use crate::virtual_polynomial::VirtualPolynomial;
+use ark_ff::Field;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm},
+    univariate::SparsePolynomial as UniSparsePolynomial,
+};
+use ark_serialize::CanonicalSerialize;
+use sha2::{Digest, Sha256};
+
+/// Fiat-Shamir transcript for the non-interactive Sumcheck Protocol.
+/// Every message of the Prover is absorbed into a running SHA-256 state,
+/// and each challenge is derived from the hash of everything absorbed so far.
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: Sha256,
+}
+
+impl Transcript {
+    /// Creates a new transcript bound to a protocol label
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Transcript {
+            hasher: Sha256::new(),
+        };
+        transcript.append_message(b"protocol", label);
+        transcript
+    }
+
+    /// Absorbs a labeled message; both parts are length-prefixed so that
+    /// different message splits never produce the same hash input
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.hasher.update((label.len() as u64).to_le_bytes());
+        self.hasher.update(label);
+        self.hasher.update((message.len() as u64).to_le_bytes());
+        self.hasher.update(message);
+    }
+
+    /// Absorbs a single field element in its canonical compressed encoding
+    pub fn append_field_element<F: Field>(&mut self, label: &[u8], element: &F) {
+        let mut bytes = Vec::new();
+        element
+            .serialize_compressed(&mut bytes)
+            .expect("serializing a field element into a Vec cannot fail");
+        self.append_message(label, &bytes);
+    }
+
+    /// Absorbs the sum claimed by the Prover
+    pub fn append_claimed_sum<F: Field>(&mut self, claimed_sum: &F) {
+        self.append_field_element(b"claimed_sum", claimed_sum);
+    }
+
+    /// Absorbs the shape of the polynomial: its number of variables and degree. The shape
+    /// alone does not bind the challenges to the polynomial, so the polynomial itself must be
+    /// absorbed as well, e.g. with `append_polynomial`.
+    pub fn append_polynomial_info(&mut self, num_variables: usize, max_degree: usize) {
+        self.append_message(b"num_variables", &(num_variables as u64).to_le_bytes());
+        self.append_message(b"max_degree", &(max_degree as u64).to_le_bytes());
+    }
+
+    /// Absorbs the polynomial whose sum is proven, so that a Prover cannot choose it
+    /// after seeing the challenges
+    pub fn append_polynomial<F: Field>(&mut self, polynomial: &SparsePolynomial<F, SparseTerm>) {
+        let mut bytes = Vec::new();
+        polynomial
+            .serialize_compressed(&mut bytes)
+            .expect("serializing a polynomial into a Vec cannot fail");
+        self.append_message(b"polynomial", &bytes);
+    }
+
+    /// Absorbs a multilinear polynomial given by its evaluations over the boolean hypercube
+    pub fn append_multilinear<F: Field>(&mut self, evaluations: &[F]) {
+        let mut bytes = Vec::new();
+        evaluations
+            .serialize_compressed(&mut bytes)
+            .expect("serializing field elements into a Vec cannot fail");
+        self.append_message(b"multilinear", &bytes);
+    }
+
+    /// Absorbs a `VirtualPolynomial`: the evaluations of every factor,
+    /// then the coefficient and factor indices of every product
+    pub fn append_virtual_polynomial<F: Field>(&mut self, polynomial: &VirtualPolynomial<F>) {
+        for mle in polynomial.mles() {
+            self.append_multilinear(&mle.evaluations);
+        }
+        for (coefficient, factors) in polynomial.products() {
+            self.append_field_element(b"product_coefficient", coefficient);
+            let indices: Vec<u8> = factors
+                .iter()
+                .flat_map(|index| (*index as u64).to_le_bytes())
+                .collect();
+            self.append_message(b"product_factors", &indices);
+        }
+    }
+
+    /// Absorbs the univariate polynomial sent by the Prover in one round
+    pub fn append_round_polynomial<F: Field>(&mut self, polynomial: &UniSparsePolynomial<F>) {
+        let mut bytes = Vec::new();
+        polynomial
+            .serialize_compressed(&mut bytes)
+            .expect("serializing a polynomial into a Vec cannot fail");
+        self.append_message(b"round_polynomial", &bytes);
+    }
+
+    /// Absorbs a round polynomial sent as its evaluations at 0..=degree
+    pub fn append_round_evaluations<F: Field>(&mut self, evaluations: &[F]) {
+        let mut bytes = Vec::new();
+        evaluations
+            .serialize_compressed(&mut bytes)
+            .expect("serializing field elements into a Vec cannot fail");
+        self.append_message(b"round_evaluations", &bytes);
+    }
+
+    /// Derives the next challenge from the current transcript state.
+    /// The derived seed is absorbed back, so consecutive challenges differ.
+    pub fn challenge<F: Field>(&mut self, label: &[u8]) -> F {
+        self.append_message(b"challenge", label);
+        let seed: [u8; 32] = self.hasher.clone().finalize().into();
+        self.append_message(b"challenge_seed", &seed);
+
+        // Rejection sampling: bytes that do not encode a field element are discarded and the
+        // next attempt is tried, so every element is equally likely and no RNG is involved
+        let num_bytes = F::zero().compressed_size();
+        (0u64..)
+            .find_map(|attempt| F::from_random_bytes(&expand_seed(&seed, attempt, num_bytes)))
+            .expect("some attempt encodes a field element")
+    }
+}
+
+/// `num_bytes` pseudorandom bytes for one attempt at a challenge: the SHA-256 hashes of the
+/// seed followed by the attempt and block counters, concatenated and truncated
+fn expand_seed(seed: &[u8; 32], attempt: u64, num_bytes: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(num_bytes + 32);
+    let mut block = 0u64;
+    while bytes.len() < num_bytes {
+        let mut hasher = Sha256::new();
+        hasher.update(seed);
+        hasher.update(attempt.to_le_bytes());
+        hasher.update(block.to_le_bytes());
+        bytes.extend_from_slice(&hasher.finalize());
+        block += 1;
+    }
+    bytes.truncate(num_bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::fp128::Fq;
+
+    #[test]
+    fn test_same_messages_give_same_challenges() {
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"test");
+        t1.append_claimed_sum(&Fq::from(42));
+        t2.append_claimed_sum(&Fq::from(42));
+
+        let c1: Fq = t1.challenge(b"r");
+        let c2: Fq = t2.challenge(b"r");
+        assert_eq!(
+            c1, c2,
+            "Identical transcripts must derive identical challenges"
+        );
+
+        // Consecutive challenges must not repeat
+        let next: Fq = t1.challenge(b"r");
+        assert_ne!(c1, next, "Consecutive challenges should differ");
+    }
+
+    #[test]
+    fn test_different_messages_give_different_challenges() {
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"test");
+        t1.append_claimed_sum(&Fq::from(42));
+        t2.append_claimed_sum(&Fq::from(43));
+
+        let c1: Fq = t1.challenge(b"r");
+        let c2: Fq = t2.challenge(b"r");
+        assert_ne!(
+            c1, c2,
+            "Different claims should derive different challenges"
+        );
+    }
+
+    #[test]
+    fn test_challenges_depend_on_the_polynomial() {
+        use ark_poly::{multivariate::Term, DenseMVPolynomial};
+
+        // Same shape and same sum over the hypercube, different polynomials
+        let x0 = SparsePolynomial::from_coefficients_vec(
+            2,
+            vec![(Fq::from(1), SparseTerm::new(vec![(0, 1)]))],
+        );
+        let x1 = SparsePolynomial::from_coefficients_vec(
+            2,
+            vec![(Fq::from(1), SparseTerm::new(vec![(1, 1)]))],
+        );
+
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"test");
+        t1.append_polynomial_info(2, 1);
+        t2.append_polynomial_info(2, 1);
+        t1.append_polynomial(&x0);
+        t2.append_polynomial(&x1);
+
+        let c1: Fq = t1.challenge(b"r");
+        let c2: Fq = t2.challenge(b"r");
+        assert_ne!(
+            c1, c2,
+            "Different polynomials should derive different challenges"
+        );
+    }
+
+    #[test]
+    fn test_challenge_derivation_is_stable() {
+        // Challenges are part of the proof format: this value must not change with
+        // dependency upgrades
+        let mut transcript = Transcript::new(b"test");
+        transcript.append_claimed_sum(&Fq::from(42));
+        let challenge: Fq = transcript.challenge(b"r");
+        assert_eq!(
+            challenge,
+            Fq::from(78948650127410857088822443264195159486u128)
+        );
+    }
+}
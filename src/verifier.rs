@@ -1,76 +1,408 @@
 use ark_ff::Field;
+use ark_poly::multivariate::{SparsePolynomial, SparseTerm};
 use ark_poly::univariate::SparsePolynomial as UniSparsePolynomial;
 use ark_poly::Polynomial;
-use rand::thread_rng;
+use rand::Rng;
+use tracing::{debug, debug_span, field, Span};
 
-pub struct Verifier<F: Field> {
+use crate::challenge::{ChallengeSource, OsChallenges};
+use crate::error::SumcheckError;
+use crate::polynomial::{interpolate_evaluations, variable_degrees};
+use crate::transcript::Transcript;
+
+/// The claim left over once every round has passed: the polynomial evaluated
+/// at `point` must equal `expected_value`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subclaim<F: Field> {
+    pub point: Vec<F>,
+    pub expected_value: F,
+}
+
+impl<F: Field> Subclaim<F> {
+    /// Discharges the subclaim by querying the polynomial oracle at the challenge point
+    pub fn check<O: EvaluationOracle<F> + ?Sized>(
+        &self,
+        oracle: &O,
+    ) -> Result<(), SumcheckError<F>> {
+        let evaluation = oracle.evaluate_at(&self.point);
+        if evaluation != self.expected_value {
+            return Err(SumcheckError::FinalEvaluationMismatch {
+                expected: self.expected_value,
+                got: evaluation,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Oracle access to the polynomial being summed, used for the final check
+pub trait EvaluationOracle<F: Field> {
+    fn evaluate_at(&self, point: &[F]) -> F;
+}
+
+impl<F: Field, O: Fn(&[F]) -> F> EvaluationOracle<F> for O {
+    fn evaluate_at(&self, point: &[F]) -> F {
+        self(point)
+    }
+}
+
+impl<F: Field> EvaluationOracle<F> for SparsePolynomial<F, SparseTerm> {
+    fn evaluate_at(&self, point: &[F]) -> F {
+        self.evaluate(&point.to_vec())
+    }
+}
+
+/// The Verifier's state for one run of the protocol. Its fields are public and its round
+/// methods can be called in any order, so the caller is responsible for following the
+/// protocol; `session::VerifierSession` enforces the order instead.
+pub struct Verifier<F: Field, C: ChallengeSource<F> = OsChallenges> {
     pub num_variables: usize,
     pub expected_sum: F,
     pub challenge_values: Vec<F>, // Stores the challenge values chosen by the Verifier
+    pub degree_bounds: Vec<usize>, // Maximum degree of the round polynomial for each variable
+    pub current_claim: F,         // The value the next round polynomial must sum to over {0, 1}
+    pub challenge_source: C,      // Where the challenge of every round comes from
 }
 
 impl<F: Field> Verifier<F> {
-    /// Initializes the Verifier with the expected sum and the number of variables
-    pub fn new(num_variables: usize, expected_sum: F) -> Self {
+    /// Initializes the Verifier with the expected sum and the degree bound of every variable.
+    /// The number of variables is the number of degree bounds. Challenges are fresh
+    /// randomness; use `with_challenge_source` to take them from elsewhere.
+    pub fn new(expected_sum: F, degree_bounds: Vec<usize>) -> Self {
         Verifier {
-            num_variables,
+            num_variables: degree_bounds.len(),
             expected_sum,
             challenge_values: Vec::new(), // Initially, no challenges have been chosen
+            degree_bounds,
+            current_claim: expected_sum,
+            challenge_source: OsChallenges,
+        }
+    }
+
+    /// Initializes the Verifier with degree bounds taken from the polynomial being checked
+    pub fn from_polynomial(polynomial: &SparsePolynomial<F, SparseTerm>, expected_sum: F) -> Self {
+        Self::new(expected_sum, variable_degrees(polynomial))
+    }
+}
+
+impl<F: Field, C: ChallengeSource<F>> Verifier<F, C> {
+    /// Takes the challenges of the remaining rounds from `challenge_source`, e.g. a
+    /// `Transcript` for the non-interactive protocol or `ScriptedChallenges` in tests
+    pub fn with_challenge_source<D: ChallengeSource<F>>(
+        self,
+        challenge_source: D,
+    ) -> Verifier<F, D> {
+        Verifier {
+            num_variables: self.num_variables,
+            expected_sum: self.expected_sum,
+            challenge_values: self.challenge_values,
+            degree_bounds: self.degree_bounds,
+            current_claim: self.current_claim,
+            challenge_source,
         }
     }
 
-    /// Chooses a random challenge value (0 or 1) for the current round
+    /// Draws the challenge for the current round from the challenge source
     /// and stores it in the challenge_values list
-    pub fn choose_challenge(&mut self) {
-        // let mut rng = test_rng(); // for test
-        let mut rng = thread_rng();
-        let challenge = F::rand(&mut rng); // Randomly select a field element
+    pub fn choose_challenge(&mut self) -> Result<(), SumcheckError<F>> {
+        let challenge = self.challenge_source.next_challenge()?;
         self.challenge_values.push(challenge);
-        println!("Selected random challenge: {:?}", challenge);
+        debug!(?challenge, "selected challenge");
+        Ok(())
     }
 
-    /// Verifies the reduced univariate polynomial by evaluating it at 0 and 1
-    /// and checks if the sum of these evaluations matches the expected sum
-    pub fn verify_polynomial(&self, polynomial: &UniSparsePolynomial<F>, prev_eval: &F) -> bool {
-        let sum_at_0 = polynomial.evaluate(&F::zero());
-        let sum_at_1 = polynomial.evaluate(&F::one());
+    /// Verifies the reduced univariate polynomial of the current round: its degree must be
+    /// within the bound of the variable, and its evaluations at 0 and 1 must sum to the
+    /// expected sum
+    pub fn verify_polynomial(
+        &self,
+        polynomial: &UniSparsePolynomial<F>,
+        prev_eval: &F,
+    ) -> Result<(), SumcheckError<F>> {
+        self.check_round(polynomial.degree(), prev_eval, || {
+            (
+                polynomial.evaluate(&F::zero()),
+                polynomial.evaluate(&F::one()),
+            )
+        })
+    }
+
+    /// Same as `verify_polynomial`, for a round polynomial sent as its evaluations
+    /// at 0..=degree. There must be at least the evaluations at 0 and 1, and their number
+    /// is checked against the degree bound before any interpolation, so an oversized
+    /// message costs nothing to reject.
+    pub fn verify_evaluations(
+        &self,
+        evaluations: &[F],
+        prev_eval: &F,
+    ) -> Result<(), SumcheckError<F>> {
+        if evaluations.len() < 2 {
+            return Err(SumcheckError::MalformedEncoding(format!(
+                "a round polynomial is sent as at least its evaluations at 0 and 1, got {}",
+                evaluations.len()
+            )));
+        }
+        self.check_round(evaluations.len() - 1, prev_eval, || {
+            (
+                interpolate_evaluations(evaluations, F::zero()),
+                interpolate_evaluations(evaluations, F::one()),
+            )
+        })
+    }
 
-        let verified = sum_at_0 + sum_at_1 == *prev_eval;
-        println!(
-            "Verifier checks reduced polynomial, evaluated at 0: {}, at 1: {} to be: {}",
-            sum_at_0, sum_at_1, prev_eval
+    /// Checks the degree of the current round polynomial against its bound, then its
+    /// evaluations at 0 and 1, computed by `evaluate_at_0_and_1`, against the expected sum
+    fn check_round(
+        &self,
+        degree: usize,
+        prev_eval: &F,
+        evaluate_at_0_and_1: impl FnOnce() -> (F, F),
+    ) -> Result<(), SumcheckError<F>> {
+        let round = self.challenge_values.len();
+
+        // Soundness relies on the degree of each round polynomial being bounded
+        let bound = match self.degree_bounds.get(round) {
+            Some(bound) => *bound,
+            None => {
+                return Err(SumcheckError::WrongRoundCount {
+                    expected: self.num_variables,
+                    got: round + 1,
+                })
+            }
+        };
+        if degree > bound {
+            debug!(degree, bound, "round polynomial exceeds the degree bound");
+            return Err(SumcheckError::DegreeTooHigh {
+                round,
+                degree,
+                bound,
+            });
+        }
+
+        let (sum_at_0, sum_at_1) = evaluate_at_0_and_1();
+        debug!(
+            ?sum_at_0,
+            ?sum_at_1,
+            expected = ?prev_eval,
+            "verifier checks reduced polynomial"
         );
 
-        verified
+        if sum_at_0 + sum_at_1 != *prev_eval {
+            debug!("round polynomial does not sum to the expected claim");
+            return Err(SumcheckError::SumMismatch {
+                round,
+                expected: *prev_eval,
+                got: sum_at_0 + sum_at_1,
+            });
+        }
+        Ok(())
     }
 
     /// Sends the challenge to the prover and receives the reduced univariate polynomial
-    /// Verifies the polynomial and returns it if successful, otherwise returns None
+    /// Verifies the polynomial and returns its evaluation at the challenge if successful
     pub fn verify_and_challenge(
         &mut self,
         poly: &UniSparsePolynomial<F>,
         variable_index: usize,
         expected_sum: &F,
-    ) -> Option<F> {
-        // Choose a random challenge value
-        self.choose_challenge();
+    ) -> Result<F, SumcheckError<F>> {
+        let span = Self::round_span(variable_index, expected_sum);
+        let _guard = span.enter();
+        self.check_variable_index(variable_index)?;
+        debug!(?poly, "received reduced polynomial");
+
+        // Verify the reduced polynomial
+        self.verify_polynomial(poly, expected_sum)?;
+
+        // Choose the challenge, after the source has seen the polynomial
+        self.challenge_source.absorb_polynomial(poly);
+        self.choose_challenge()?;
+
+        Ok(self.accept_round(poly, variable_index))
+    }
+
+    /// Same as `verify_and_challenge`, for a round polynomial sent as its evaluations
+    /// at 0..=degree; the next claim is obtained by interpolating at the challenge
+    pub fn verify_evaluations_and_challenge(
+        &mut self,
+        evaluations: &[F],
+        variable_index: usize,
+        expected_sum: &F,
+    ) -> Result<F, SumcheckError<F>> {
+        let span = Self::round_span(variable_index, expected_sum);
+        let _guard = span.enter();
+        self.check_variable_index(variable_index)?;
+        debug!(?evaluations, "received reduced polynomial evaluations");
 
-        // Reduced polynomial form the prover
-        let reduced_polynomial = poly;
+        self.verify_evaluations(evaluations, expected_sum)?;
 
-        // Output the reduced polynomial for debugging purposes
-        println!("Reduced Polynomial: {:?}", reduced_polynomial);
+        self.challenge_source.absorb_evaluations(evaluations);
+        self.choose_challenge()?;
 
-        // Verify the reduced polynomial
-        if self.verify_polynomial(&reduced_polynomial, expected_sum) {
-            Some(
-                reduced_polynomial
-                    .clone()
-                    .evaluate(&self.challenge_values[variable_index]),
-            )
-        } else {
-            println!("Verification failed");
-            None
+        Ok(self.accept_evaluations(evaluations, variable_index))
+    }
+
+    /// Span covering one verifier round; the challenge is recorded once it is chosen
+    fn round_span(variable_index: usize, expected_sum: &F) -> Span {
+        debug_span!(
+            "verifier_round",
+            round = variable_index,
+            claim = ?expected_sum,
+            challenge = field::Empty
+        )
+    }
+
+    /// The variable of a round must exist and be the next one without a challenge
+    fn check_variable_index(&self, variable_index: usize) -> Result<(), SumcheckError<F>> {
+        if variable_index >= self.num_variables {
+            return Err(SumcheckError::VariableOutOfRange {
+                variable: variable_index,
+                num_variables: self.num_variables,
+            });
+        }
+        if variable_index != self.challenge_values.len() {
+            return Err(SumcheckError::WrongRoundCount {
+                expected: self.challenge_values.len(),
+                got: variable_index,
+            });
+        }
+        Ok(())
+    }
+
+    /// Evaluates the accepted round polynomial at its challenge,
+    /// which becomes the claim for the next round
+    fn accept_round(&mut self, poly: &UniSparsePolynomial<F>, variable_index: usize) -> F {
+        let challenge = self.challenge_values[variable_index];
+        self.current_claim = poly.evaluate(&challenge);
+        self.log_accepted_round(&challenge);
+        self.current_claim
+    }
+
+    /// Interpolates the accepted round evaluations at their challenge,
+    /// which becomes the claim for the next round
+    fn accept_evaluations(&mut self, evaluations: &[F], variable_index: usize) -> F {
+        let challenge = self.challenge_values[variable_index];
+        self.current_claim = interpolate_evaluations(evaluations, challenge);
+        self.log_accepted_round(&challenge);
+        self.current_claim
+    }
+
+    fn log_accepted_round(&self, challenge: &F) {
+        Span::current().record("challenge", field::debug(challenge));
+        debug!(next_claim = ?self.current_claim, "round accepted");
+    }
+
+    /// Ends the protocol once every round has passed and returns the subclaim
+    /// that the polynomial evaluates to the final claim at the challenge point.
+    /// The subclaim is not checked here; use `finalize_with_oracle` for that.
+    pub fn finalize(&self) -> Result<Subclaim<F>, SumcheckError<F>> {
+        if self.challenge_values.len() != self.num_variables {
+            return Err(SumcheckError::WrongRoundCount {
+                expected: self.num_variables,
+                got: self.challenge_values.len(),
+            });
         }
+
+        Ok(Subclaim {
+            point: self.challenge_values.clone(),
+            expected_value: self.current_claim,
+        })
+    }
+
+    /// Ends the protocol with the final check: the oracle's evaluation at the
+    /// challenge point must equal the final claim
+    pub fn finalize_with_oracle<O: EvaluationOracle<F> + ?Sized>(
+        &self,
+        oracle: &O,
+    ) -> Result<Subclaim<F>, SumcheckError<F>> {
+        let subclaim = self.finalize()?;
+        subclaim.check(oracle)?;
+        debug!(
+            expected_value = ?subclaim.expected_value,
+            "verifier accepts the final evaluation"
+        );
+        Ok(subclaim)
+    }
+}
+
+/// The per-call challenge methods from before `ChallengeSource`, kept so that existing
+/// callers still build. Each takes the challenge of one round from its argument instead
+/// of the Verifier's challenge source.
+impl<F: Field, C: ChallengeSource<F>> Verifier<F, C> {
+    /// Draws a random challenge for the current round from `rng`
+    /// and stores it in the challenge_values list
+    #[deprecated(note = "use `with_challenge_source(SeededChallenges::new(seed))` instead")]
+    pub fn choose_challenge_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let challenge = F::rand(rng);
+        self.challenge_values.push(challenge);
+        debug!(?challenge, "selected random challenge");
+    }
+
+    /// Derives the challenge for the current round from the Fiat-Shamir transcript
+    /// and stores it in the challenge_values list
+    #[deprecated(note = "use `with_challenge_source(&mut transcript)` instead")]
+    pub fn choose_challenge_from_transcript(&mut self, transcript: &mut Transcript) {
+        let challenge = transcript.challenge(b"round_challenge");
+        self.challenge_values.push(challenge);
+        debug!(?challenge, "derived challenge from transcript");
+    }
+
+    /// Same as `verify_and_challenge`, with the challenge drawn from `rng`
+    #[deprecated(note = "use `with_challenge_source` and `verify_and_challenge` instead")]
+    pub fn verify_and_challenge_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        poly: &UniSparsePolynomial<F>,
+        variable_index: usize,
+        expected_sum: &F,
+        rng: &mut R,
+    ) -> Result<F, SumcheckError<F>> {
+        let span = Self::round_span(variable_index, expected_sum);
+        let _guard = span.enter();
+        self.check_variable_index(variable_index)?;
+        self.verify_polynomial(poly, expected_sum)?;
+        #[allow(deprecated)]
+        self.choose_challenge_with_rng(rng);
+        Ok(self.accept_round(poly, variable_index))
+    }
+
+    /// Non-interactive variant of `verify_and_challenge`: the reduced polynomial is absorbed
+    /// into the transcript and the challenge is derived from it instead of being sampled
+    #[deprecated(note = "use `with_challenge_source` and `verify_and_challenge` instead")]
+    pub fn verify_and_challenge_with_transcript(
+        &mut self,
+        poly: &UniSparsePolynomial<F>,
+        variable_index: usize,
+        expected_sum: &F,
+        transcript: &mut Transcript,
+    ) -> Result<F, SumcheckError<F>> {
+        let span = Self::round_span(variable_index, expected_sum);
+        let _guard = span.enter();
+        self.check_variable_index(variable_index)?;
+        self.verify_polynomial(poly, expected_sum)?;
+        transcript.append_round_polynomial(poly);
+        #[allow(deprecated)]
+        self.choose_challenge_from_transcript(transcript);
+        Ok(self.accept_round(poly, variable_index))
+    }
+
+    /// Non-interactive variant of `verify_evaluations_and_challenge`
+    #[deprecated(
+        note = "use `with_challenge_source` and `verify_evaluations_and_challenge` instead"
+    )]
+    pub fn verify_evaluations_and_challenge_with_transcript(
+        &mut self,
+        evaluations: &[F],
+        variable_index: usize,
+        expected_sum: &F,
+        transcript: &mut Transcript,
+    ) -> Result<F, SumcheckError<F>> {
+        let span = Self::round_span(variable_index, expected_sum);
+        let _guard = span.enter();
+        self.check_variable_index(variable_index)?;
+        self.verify_evaluations(evaluations, expected_sum)?;
+        transcript.append_round_evaluations(evaluations);
+        #[allow(deprecated)]
+        self.choose_challenge_from_transcript(transcript);
+        Ok(self.accept_evaluations(evaluations, variable_index))
     }
 }
@@ -0,0 +1,285 @@
+use crate::error::SumcheckError;
+use crate::parallel::{add_assign_vec, fold_range, fold_table};
+use crate::transcript::Transcript;
+use crate::verifier::EvaluationOracle;
+use ark_ff::Field;
+use ark_poly::{DenseMultilinearExtension, Polynomial};
+use tracing::{debug, debug_span};
+
+/// A sum of products of multilinear polynomials, sum_i c_i * prod_{j in P_i} f_j(x),
+/// such as eq(x) * A(x) * B(x). The factors are kept as dense evaluation tables and
+/// are never expanded into a single `SparsePolynomial`.
+/// The fields are only set by `add_mle` and `add_product`, so every product names
+/// registered factors of the right number of variables.
+#[derive(Debug, Clone)]
+pub struct VirtualPolynomial<F: Field> {
+    num_variables: usize,
+    mles: Vec<DenseMultilinearExtension<F>>, // Distinct factors, shared between products
+    products: Vec<(F, Vec<usize>)>,          // Coefficient and indices into `mles`
+}
+
+impl<F: Field> VirtualPolynomial<F> {
+    /// Creates the zero polynomial over `num_variables` variables
+    pub fn new(num_variables: usize) -> Self {
+        VirtualPolynomial {
+            num_variables,
+            mles: Vec::new(),
+            products: Vec::new(),
+        }
+    }
+
+    /// Registers a multilinear factor and returns its index for `add_product`
+    pub fn add_mle(
+        &mut self,
+        mle: DenseMultilinearExtension<F>,
+    ) -> Result<usize, SumcheckError<F>> {
+        if mle.num_vars != self.num_variables {
+            return Err(SumcheckError::InvalidParameters(format!(
+                "factor has {} variables, expected {}",
+                mle.num_vars, self.num_variables
+            )));
+        }
+        self.mles.push(mle);
+        Ok(self.mles.len() - 1)
+    }
+
+    /// Adds `coefficient` times the product of the registered factors in `factors`
+    pub fn add_product(
+        &mut self,
+        coefficient: F,
+        factors: Vec<usize>,
+    ) -> Result<(), SumcheckError<F>> {
+        if let Some(&index) = factors.iter().find(|&&index| index >= self.mles.len()) {
+            return Err(SumcheckError::InvalidParameters(format!(
+                "factor {} is not registered, only {} factors exist",
+                index,
+                self.mles.len()
+            )));
+        }
+        self.products.push((coefficient, factors));
+        Ok(())
+    }
+
+    /// Number of variables of the polynomial and of each of its factors
+    pub fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    /// The registered factors, in the order of their indices
+    pub fn mles(&self) -> &[DenseMultilinearExtension<F>] {
+        &self.mles
+    }
+
+    /// The products, as a coefficient and the indices of their factors
+    pub fn products(&self) -> &[(F, Vec<usize>)] {
+        &self.products
+    }
+
+    /// Maximum number of factors in a product, i.e. the degree of every round polynomial
+    pub fn max_degree(&self) -> usize {
+        self.products
+            .iter()
+            .map(|(_, factors)| factors.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Calculates the sum of the polynomial over all possible input combinations of 0 and 1
+    pub fn sum_over_all_inputs(&self) -> F {
+        (0..1 << self.num_variables)
+            .map(|i| {
+                self.products
+                    .iter()
+                    .map(|(coefficient, factors)| {
+                        factors
+                            .iter()
+                            .fold(*coefficient, |acc, &f| acc * self.mles[f].evaluations[i])
+                    })
+                    .sum::<F>()
+            })
+            .sum()
+    }
+}
+
+impl<F: Field> EvaluationOracle<F> for VirtualPolynomial<F> {
+    fn evaluate_at(&self, point: &[F]) -> F {
+        let factor_evaluations: Vec<F> = self
+            .mles
+            .iter()
+            .map(|mle| mle.evaluate(&point.to_vec()))
+            .collect();
+
+        self.products
+            .iter()
+            .map(|(coefficient, factors)| {
+                factors
+                    .iter()
+                    .fold(*coefficient, |acc, &f| acc * factor_evaluations[f])
+            })
+            .sum()
+    }
+}
+
+/// Prover for a `VirtualPolynomial`. Every factor keeps its own bookkeeping table, folded
+/// with each challenge, and each round polynomial is sent as its evaluations at 0..=degree.
+pub struct VirtualPolynomialProver<F: Field> {
+    pub tables: Vec<Vec<F>>, // Bookkeeping table of each factor over the unfixed variables
+    pub products: Vec<(F, Vec<usize>)>,
+    pub num_variables: usize,
+    pub degree: usize,
+    pub num_fixed: usize, // Number of variables already fixed to a challenge
+}
+
+impl<F: Field> VirtualPolynomialProver<F> {
+    /// Create a new Prover for the given virtual polynomial
+    pub fn new(polynomial: &VirtualPolynomial<F>) -> Self {
+        VirtualPolynomialProver {
+            tables: polynomial
+                .mles
+                .iter()
+                .map(|mle| mle.evaluations.clone())
+                .collect(),
+            products: polynomial.products.clone(),
+            num_variables: polynomial.num_variables,
+            degree: polynomial.max_degree().max(1), // g(0) and g(1) are always sent
+            num_fixed: 0,
+        }
+    }
+
+    /// Fixes the lowest unfixed variable of every factor to `challenge`
+    fn fix_variable(&mut self, challenge: F) {
+        for table in self.tables.iter_mut() {
+            fold_table(table, challenge);
+        }
+        self.num_fixed += 1;
+    }
+
+    /// Computes the round polynomial of `target_var` as its evaluations at 0..=degree,
+    /// fixing the previous variables to `randoms` and summing over the remaining ones.
+    /// Rounds must be run in order, since folding cannot be undone.
+    pub fn reduce_to_evaluations(
+        &mut self,
+        target_var: usize,
+        randoms: &[F],
+    ) -> Result<Vec<F>, SumcheckError<F>> {
+        let _span = debug_span!("prover_round", round = target_var).entered();
+
+        if target_var >= self.num_variables {
+            return Err(SumcheckError::VariableOutOfRange {
+                variable: target_var,
+                num_variables: self.num_variables,
+            });
+        }
+        if randoms.len() != target_var || target_var < self.num_fixed {
+            return Err(SumcheckError::WrongRoundCount {
+                expected: self.num_fixed,
+                got: randoms.len(),
+            });
+        }
+
+        // Fold in the challenges received since the previous round
+        for &challenge in &randoms[self.num_fixed..] {
+            self.fix_variable(challenge);
+        }
+
+        let half = 1 << (self.num_variables - target_var - 1);
+        // Each partial result carries a scratch buffer for the factor values
+        let (evaluations, _) = fold_range(
+            half,
+            || {
+                (
+                    vec![F::zero(); self.degree + 1],
+                    vec![F::zero(); self.tables.len()],
+                )
+            },
+            |(mut evaluations, mut factor_values), k| {
+                for (t, evaluation) in evaluations.iter_mut().enumerate() {
+                    // Every factor is linear in the target variable: f(t) = a + t * (b - a)
+                    let t = F::from(t as u64);
+                    for (value, table) in factor_values.iter_mut().zip(&self.tables) {
+                        *value = table[2 * k] + t * (table[2 * k + 1] - table[2 * k]);
+                    }
+                    for (coefficient, factors) in &self.products {
+                        *evaluation += factors
+                            .iter()
+                            .fold(*coefficient, |acc, &f| acc * factor_values[f]);
+                    }
+                }
+                (evaluations, factor_values)
+            },
+            |(evaluations, factor_values), (other, _)| {
+                (add_assign_vec(evaluations, other), factor_values)
+            },
+        );
+
+        debug!(?evaluations, "reduced to round evaluations");
+        Ok(evaluations)
+    }
+
+    /// Non-interactive round: computes the evaluations for `target_var`, absorbs them
+    /// into the transcript and appends the derived challenge to `randoms`
+    pub fn reduce_with_transcript(
+        &mut self,
+        target_var: usize,
+        randoms: &mut Vec<F>,
+        transcript: &mut Transcript,
+    ) -> Result<Vec<F>, SumcheckError<F>> {
+        let evaluations = self.reduce_to_evaluations(target_var, randoms)?;
+        transcript.append_round_evaluations(&evaluations);
+        randoms.push(transcript.challenge(b"round_challenge"));
+        Ok(evaluations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mle::mle_from_evaluations;
+    use crate::polynomial::interpolate_evaluations;
+    use ark_std::{test_rng, UniformRand};
+    use ark_test_curves::fp128::Fq;
+
+    fn random_mle(num_variables: usize) -> DenseMultilinearExtension<Fq> {
+        let mut rng = test_rng();
+        mle_from_evaluations(
+            (0..1 << num_variables)
+                .map(|_| Fq::rand(&mut rng))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_round_evaluations_sum_to_claim() {
+        // 2 * A * B * C + 5 * B
+        let mut poly = VirtualPolynomial::new(3);
+        let a = poly.add_mle(random_mle(3)).unwrap();
+        let b = poly.add_mle(random_mle(3)).unwrap();
+        let c = poly.add_mle(random_mle(3)).unwrap();
+        poly.add_product(Fq::from(2), vec![a, b, c]).unwrap();
+        poly.add_product(Fq::from(5), vec![b]).unwrap();
+        assert_eq!(poly.max_degree(), 3);
+
+        let mut prover = VirtualPolynomialProver::new(&poly);
+        let mut claim = poly.sum_over_all_inputs();
+        let mut randoms = Vec::new();
+        let mut rng = test_rng();
+        for i in 0..3 {
+            let evaluations = prover.reduce_to_evaluations(i, &randoms).unwrap();
+            assert_eq!(evaluations.len(), 4);
+            assert_eq!(evaluations[0] + evaluations[1], claim);
+
+            let r = Fq::rand(&mut rng);
+            claim = interpolate_evaluations(&evaluations, r);
+            randoms.push(r);
+        }
+        assert_eq!(poly.evaluate_at(&randoms), claim);
+    }
+
+    #[test]
+    fn test_rejects_mismatched_factors() {
+        let mut poly = VirtualPolynomial::new(3);
+        assert!(poly.add_mle(random_mle(2)).is_err());
+        assert!(poly.add_product(Fq::from(1), vec![0]).is_err());
+    }
+}
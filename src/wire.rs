@@ -0,0 +1,550 @@
+use crate::batch::BatchedSumcheckProof;
+use crate::error::SumcheckError;
+use crate::proof::{CompressedSumcheckProof, SumcheckProof, VirtualSumcheckProof};
+use crate::verifier::Subclaim;
+use ark_ff::Field;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm, Term},
+    univariate::SparsePolynomial as UniSparsePolynomial,
+    DenseMVPolynomial,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Version written into every encoding. Bump it whenever a wire type changes shape,
+/// so that an old decoder refuses a new message instead of misreading it.
+pub const WIRE_FORMAT_VERSION: u32 = 1;
+
+/// Largest number of variables of a decoded polynomial. Proving sums over the 2^v points
+/// of the boolean hypercube, so larger polynomials could not be used anyway.
+pub const MAX_WIRE_NUM_VARIABLES: usize = 32;
+
+/// Largest total degree of a term of a decoded polynomial. The round polynomials of a
+/// proof have one coefficient per degree.
+pub const MAX_WIRE_TERM_DEGREE: usize = 1 << 16;
+
+/// Every encoded message is wrapped with the format version
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    payload: T,
+}
+
+/// A term of a multivariate polynomial: coefficient and (variable, power) pairs
+#[derive(Serialize, Deserialize)]
+pub struct WireTerm {
+    coefficient: String,
+    powers: Vec<(usize, usize)>,
+}
+
+/// Wire representation of a `SparsePolynomial<F, SparseTerm>`
+#[derive(Serialize, Deserialize)]
+pub struct WirePolynomial {
+    num_variables: usize,
+    terms: Vec<WireTerm>,
+}
+
+/// A univariate polynomial as (degree, coefficient) pairs
+type WireUnivariate = Vec<(usize, String)>;
+
+/// Wire representation of a `SumcheckProof`
+#[derive(Serialize, Deserialize)]
+pub struct WireProof {
+    claimed_sum: String,
+    round_polynomials: Vec<WireUnivariate>,
+}
+
+/// Wire representation of a `BatchedSumcheckProof`
+#[derive(Serialize, Deserialize)]
+pub struct WireBatchedProof {
+    claimed_sums: Vec<String>,
+    round_polynomials: Vec<WireUnivariate>,
+    final_evaluations: Vec<String>,
+}
+
+/// Wire representation of a `VirtualSumcheckProof`
+#[derive(Serialize, Deserialize)]
+pub struct WireVirtualProof {
+    claimed_sum: String,
+    round_evaluations: Vec<Vec<String>>,
+}
+
+/// Wire representation of a `CompressedSumcheckProof`
+#[derive(Serialize, Deserialize)]
+pub struct WireCompressedProof {
+    claimed_sum: String,
+    round_evaluations: Vec<Vec<String>>,
+}
+
+/// Wire representation of a `Subclaim`, i.e. the challenges and the final claim
+#[derive(Serialize, Deserialize)]
+pub struct WireSubclaim {
+    point: Vec<String>,
+    expected_value: String,
+}
+
+/// Encodes a field element as the hex string of its canonical compressed bytes,
+/// so that the encoding does not depend on the in-memory Montgomery form
+fn encode_field<F: Field>(element: &F) -> String {
+    let mut bytes = Vec::new();
+    element
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a Vec cannot fail");
+    hex::encode(bytes)
+}
+
+fn decode_field<F: Field>(encoded: &str) -> Result<F, SumcheckError<F>> {
+    let bytes = hex::decode(encoded).map_err(|e| {
+        SumcheckError::MalformedEncoding(format!("field element {:?}: {}", encoded, e))
+    })?;
+    F::deserialize_compressed(bytes.as_slice()).map_err(|e| {
+        SumcheckError::MalformedEncoding(format!("field element {:?}: {}", encoded, e))
+    })
+}
+
+fn decode_fields<F: Field>(encoded: &[String]) -> Result<Vec<F>, SumcheckError<F>> {
+    encoded.iter().map(|e| decode_field(e)).collect()
+}
+
+fn encode_univariates<F: Field>(polynomials: &[UniSparsePolynomial<F>]) -> Vec<WireUnivariate> {
+    polynomials
+        .iter()
+        .map(|polynomial| {
+            polynomial
+                .iter()
+                .map(|(degree, coeff)| (*degree, encode_field(coeff)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Decodes univariate polynomials, which must be in the canonical form written by
+/// `encode_univariates`: strictly increasing degrees and a non-zero leading coefficient
+fn decode_univariates<F: Field>(
+    encoded: &[WireUnivariate],
+) -> Result<Vec<UniSparsePolynomial<F>>, SumcheckError<F>> {
+    encoded
+        .iter()
+        .map(|coefficients| {
+            let coefficients = coefficients
+                .iter()
+                .map(|(degree, coeff)| Ok((*degree, decode_field::<F>(coeff)?)))
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(window) = coefficients.windows(2).find(|w| w[0].0 >= w[1].0) {
+                return Err(SumcheckError::MalformedEncoding(format!(
+                    "univariate degrees must be strictly increasing, got {} then {}",
+                    window[0].0, window[1].0
+                )));
+            }
+            if let Some((degree, _)) = coefficients.last().filter(|(_, coeff)| coeff.is_zero()) {
+                return Err(SumcheckError::MalformedEncoding(format!(
+                    "zero leading coefficient of degree {}",
+                    degree
+                )));
+            }
+            Ok(UniSparsePolynomial::from_coefficients_vec(coefficients))
+        })
+        .collect()
+}
+
+/// Checks that term powers are in the canonical form written by `to_wire`: strictly
+/// increasing variables and no zero power, so that no two powers are merged on decoding.
+/// The total degree of the term must not exceed `MAX_WIRE_TERM_DEGREE`.
+fn check_powers<F: Field>(powers: &[(usize, usize)]) -> Result<(), SumcheckError<F>> {
+    if let Some(window) = powers.windows(2).find(|w| w[0].0 >= w[1].0) {
+        return Err(SumcheckError::MalformedEncoding(format!(
+            "term variables must be strictly increasing, got {} then {}",
+            window[0].0, window[1].0
+        )));
+    }
+    if let Some((variable, _)) = powers.iter().find(|(_, power)| *power == 0) {
+        return Err(SumcheckError::MalformedEncoding(format!(
+            "zero power of variable {}",
+            variable
+        )));
+    }
+    match powers
+        .iter()
+        .try_fold(0usize, |degree, (_, power)| degree.checked_add(*power))
+    {
+        Some(degree) if degree <= MAX_WIRE_TERM_DEGREE => Ok(()),
+        _ => Err(SumcheckError::MalformedEncoding(format!(
+            "term degree exceeds the maximum of {}",
+            MAX_WIRE_TERM_DEGREE
+        ))),
+    }
+}
+
+/// Stable, versioned encodings of protocol messages, so that a proof produced on one
+/// machine can be checked on another. Every type round-trips through JSON, bincode,
+/// and a hex string of the bincode bytes.
+pub trait WireFormat<F: Field>: Sized {
+    #[doc(hidden)]
+    type Wire: Serialize + DeserializeOwned;
+
+    #[doc(hidden)]
+    fn to_wire(&self) -> Self::Wire;
+
+    #[doc(hidden)]
+    fn from_wire(wire: Self::Wire) -> Result<Self, SumcheckError<F>>;
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(&envelope(self)).expect("wire types always serialize")
+    }
+
+    fn from_json(json: &str) -> Result<Self, SumcheckError<F>> {
+        let envelope = serde_json::from_str(json)
+            .map_err(|e| SumcheckError::MalformedEncoding(format!("invalid JSON: {}", e)))?;
+        open_envelope(envelope)
+    }
+
+    fn to_bincode(&self) -> Vec<u8> {
+        bincode::serialize(&envelope(self)).expect("wire types always serialize")
+    }
+
+    fn from_bincode(bytes: &[u8]) -> Result<Self, SumcheckError<F>> {
+        let envelope = bincode::deserialize(bytes)
+            .map_err(|e| SumcheckError::MalformedEncoding(format!("invalid bincode: {}", e)))?;
+        open_envelope(envelope)
+    }
+
+    fn to_hex(&self) -> String {
+        hex::encode(self.to_bincode())
+    }
+
+    fn from_hex(encoded: &str) -> Result<Self, SumcheckError<F>> {
+        let bytes = hex::decode(encoded.trim())
+            .map_err(|e| SumcheckError::MalformedEncoding(format!("invalid hex: {}", e)))?;
+        Self::from_bincode(&bytes)
+    }
+}
+
+fn envelope<F: Field, T: WireFormat<F>>(value: &T) -> Envelope<T::Wire> {
+    Envelope {
+        version: WIRE_FORMAT_VERSION,
+        payload: value.to_wire(),
+    }
+}
+
+fn open_envelope<F: Field, T: WireFormat<F>>(
+    envelope: Envelope<T::Wire>,
+) -> Result<T, SumcheckError<F>> {
+    if envelope.version != WIRE_FORMAT_VERSION {
+        return Err(SumcheckError::MalformedEncoding(format!(
+            "unsupported wire format version {}, expected {}",
+            envelope.version, WIRE_FORMAT_VERSION
+        )));
+    }
+    T::from_wire(envelope.payload)
+}
+
+impl<F: Field> WireFormat<F> for SparsePolynomial<F, SparseTerm> {
+    type Wire = WirePolynomial;
+
+    fn to_wire(&self) -> WirePolynomial {
+        WirePolynomial {
+            num_variables: self.num_vars,
+            terms: self
+                .terms
+                .iter()
+                .map(|(coeff, term)| WireTerm {
+                    coefficient: encode_field(coeff),
+                    powers: term.iter().copied().collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn from_wire(wire: WirePolynomial) -> Result<Self, SumcheckError<F>> {
+        if wire.num_variables > MAX_WIRE_NUM_VARIABLES {
+            return Err(SumcheckError::MalformedEncoding(format!(
+                "{} variables exceed the maximum of {}",
+                wire.num_variables, MAX_WIRE_NUM_VARIABLES
+            )));
+        }
+        let mut terms = Vec::with_capacity(wire.terms.len());
+        for term in wire.terms {
+            if let Some(&(variable, _)) = term
+                .powers
+                .iter()
+                .find(|(variable, _)| *variable >= wire.num_variables)
+            {
+                return Err(SumcheckError::VariableOutOfRange {
+                    variable,
+                    num_variables: wire.num_variables,
+                });
+            }
+            check_powers(&term.powers)?;
+            terms.push((
+                decode_field(&term.coefficient)?,
+                SparseTerm::new(term.powers),
+            ));
+        }
+        Ok(SparsePolynomial::from_coefficients_vec(
+            wire.num_variables,
+            terms,
+        ))
+    }
+}
+
+impl<F: Field> WireFormat<F> for SumcheckProof<F> {
+    type Wire = WireProof;
+
+    fn to_wire(&self) -> WireProof {
+        WireProof {
+            claimed_sum: encode_field(&self.claimed_sum),
+            round_polynomials: encode_univariates(&self.round_polynomials),
+        }
+    }
+
+    fn from_wire(wire: WireProof) -> Result<Self, SumcheckError<F>> {
+        Ok(SumcheckProof {
+            claimed_sum: decode_field(&wire.claimed_sum)?,
+            round_polynomials: decode_univariates(&wire.round_polynomials)?,
+        })
+    }
+}
+
+impl<F: Field> WireFormat<F> for BatchedSumcheckProof<F> {
+    type Wire = WireBatchedProof;
+
+    fn to_wire(&self) -> WireBatchedProof {
+        WireBatchedProof {
+            claimed_sums: self.claimed_sums.iter().map(encode_field).collect(),
+            round_polynomials: encode_univariates(&self.round_polynomials),
+            final_evaluations: self.final_evaluations.iter().map(encode_field).collect(),
+        }
+    }
+
+    fn from_wire(wire: WireBatchedProof) -> Result<Self, SumcheckError<F>> {
+        Ok(BatchedSumcheckProof {
+            claimed_sums: decode_fields(&wire.claimed_sums)?,
+            round_polynomials: decode_univariates(&wire.round_polynomials)?,
+            final_evaluations: decode_fields(&wire.final_evaluations)?,
+        })
+    }
+}
+
+impl<F: Field> WireFormat<F> for VirtualSumcheckProof<F> {
+    type Wire = WireVirtualProof;
+
+    fn to_wire(&self) -> WireVirtualProof {
+        WireVirtualProof {
+            claimed_sum: encode_field(&self.claimed_sum),
+            round_evaluations: self
+                .round_evaluations
+                .iter()
+                .map(|evaluations| evaluations.iter().map(encode_field).collect())
+                .collect(),
+        }
+    }
+
+    fn from_wire(wire: WireVirtualProof) -> Result<Self, SumcheckError<F>> {
+        Ok(VirtualSumcheckProof {
+            claimed_sum: decode_field(&wire.claimed_sum)?,
+            round_evaluations: wire
+                .round_evaluations
+                .iter()
+                .map(|evaluations| decode_fields(evaluations))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl<F: Field> WireFormat<F> for CompressedSumcheckProof<F> {
+    type Wire = WireCompressedProof;
+
+    fn to_wire(&self) -> WireCompressedProof {
+        WireCompressedProof {
+            claimed_sum: encode_field(&self.claimed_sum),
+            round_evaluations: self
+                .round_evaluations
+                .iter()
+                .map(|evaluations| evaluations.iter().map(encode_field).collect())
+                .collect(),
+        }
+    }
+
+    fn from_wire(wire: WireCompressedProof) -> Result<Self, SumcheckError<F>> {
+        Ok(CompressedSumcheckProof {
+            claimed_sum: decode_field(&wire.claimed_sum)?,
+            round_evaluations: wire
+                .round_evaluations
+                .iter()
+                .map(|evaluations| decode_fields(evaluations))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl<F: Field> WireFormat<F> for Subclaim<F> {
+    type Wire = WireSubclaim;
+
+    fn to_wire(&self) -> WireSubclaim {
+        WireSubclaim {
+            point: self.point.iter().map(encode_field).collect(),
+            expected_value: encode_field(&self.expected_value),
+        }
+    }
+
+    fn from_wire(wire: WireSubclaim) -> Result<Self, SumcheckError<F>> {
+        Ok(Subclaim {
+            point: decode_fields(&wire.point)?,
+            expected_value: decode_field(&wire.expected_value)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{prove, verify};
+    use ark_test_curves::fp128::Fq;
+
+    fn sample_polynomial() -> SparsePolynomial<Fq, SparseTerm> {
+        SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fq::from(3), SparseTerm::new(vec![(0, 3), (1, 1)])),
+                (Fq::from(3), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (-Fq::from(2), SparseTerm::new(vec![(1, 1), (2, 1)])),
+                (Fq::from(7), SparseTerm::new(vec![])),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_round_trip_in_every_encoding() {
+        let poly = sample_polynomial();
+        let (proof, challenges) = prove(&poly).unwrap();
+        let subclaim = verify(&proof, &poly).unwrap();
+        assert_eq!(subclaim.point, challenges);
+
+        let decoded_poly: SparsePolynomial<Fq, SparseTerm> =
+            WireFormat::from_json(&poly.to_json()).unwrap();
+        assert_eq!(decoded_poly, poly);
+        assert_eq!(
+            SumcheckProof::<Fq>::from_bincode(&proof.to_bincode()).unwrap(),
+            proof
+        );
+        assert_eq!(
+            SumcheckProof::<Fq>::from_hex(&proof.to_hex()).unwrap(),
+            proof
+        );
+        assert_eq!(
+            Subclaim::<Fq>::from_json(&subclaim.to_json()).unwrap(),
+            subclaim
+        );
+
+        // A proof received over the wire verifies against the same subclaim
+        let received = SumcheckProof::<Fq>::from_json(&proof.to_json()).unwrap();
+        assert_eq!(verify(&received, &poly), Ok(subclaim));
+    }
+
+    #[test]
+    fn test_rejects_malformed_input() {
+        let json = sample_polynomial().to_json();
+
+        let wrong_version = json.replacen("\"version\":1", "\"version\":2", 1);
+        assert!(matches!(
+            SparsePolynomial::<Fq, SparseTerm>::from_json(&wrong_version),
+            Err(SumcheckError::MalformedEncoding(_))
+        ));
+        assert!(matches!(
+            SparsePolynomial::<Fq, SparseTerm>::from_json("{\"version\":1}"),
+            Err(SumcheckError::MalformedEncoding(_))
+        ));
+        assert!(matches!(
+            SumcheckProof::<Fq>::from_hex("not hex"),
+            Err(SumcheckError::MalformedEncoding(_))
+        ));
+
+        let out_of_range = json.replacen("\"num_variables\":3", "\"num_variables\":2", 1);
+        assert_eq!(
+            SparsePolynomial::<Fq, SparseTerm>::from_json(&out_of_range),
+            Err(SumcheckError::VariableOutOfRange {
+                variable: 2,
+                num_variables: 2
+            })
+        );
+    }
+
+    /// A proof message with a single round polynomial given by its (degree, coefficient) pairs
+    fn proof_json(pairs: &[(usize, u64)]) -> String {
+        let coefficients: Vec<String> = pairs
+            .iter()
+            .map(|(degree, coeff)| format!("[{},\"{}\"]", degree, encode_field(&Fq::from(*coeff))))
+            .collect();
+        format!(
+            "{{\"version\":1,\"payload\":{{\"claimed_sum\":\"{}\",\"round_polynomials\":[[{}]]}}}}",
+            encode_field(&Fq::from(1)),
+            coefficients.join(",")
+        )
+    }
+
+    #[test]
+    fn test_rejects_non_canonical_round_polynomials() {
+        assert!(SumcheckProof::<Fq>::from_json(&proof_json(&[(1, 3), (5, 2)])).is_ok());
+        // Unsorted or duplicated degrees and a zero leading coefficient are refused
+        // instead of panicking
+        for pairs in [
+            vec![(5, 0), (1, 3)],
+            vec![(5, 2), (1, 3)],
+            vec![(1, 2), (1, 3)],
+            vec![(0, 3), (1, 0)],
+        ] {
+            assert!(matches!(
+                SumcheckProof::<Fq>::from_json(&proof_json(&pairs)),
+                Err(SumcheckError::MalformedEncoding(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_canonical_term_powers() {
+        let polynomial = |powers: &str| {
+            format!(
+                "{{\"version\":1,\"payload\":{{\"num_variables\":2,\"terms\":[{{\"coefficient\":\"{}\",\"powers\":{}}}]}}}}",
+                encode_field(&Fq::from(1)),
+                powers
+            )
+        };
+        assert!(
+            SparsePolynomial::<Fq, SparseTerm>::from_json(&polynomial("[[0,2],[1,1]]")).is_ok()
+        );
+        // Repeated variables would be merged by adding their powers, which can overflow,
+        // and so can the total degree of distinct variables
+        for powers in [
+            format!("[[0,{}],[0,1]]", usize::MAX),
+            format!("[[0,{}],[1,1]]", usize::MAX),
+            format!("[[0,{}]]", MAX_WIRE_TERM_DEGREE + 1),
+            "[[1,1],[0,2]]".to_string(),
+            "[[0,0]]".to_string(),
+        ] {
+            assert!(matches!(
+                SparsePolynomial::<Fq, SparseTerm>::from_json(&polynomial(&powers)),
+                Err(SumcheckError::MalformedEncoding(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_rejects_polynomials_too_large_to_prove() {
+        // Sorting these terms computes the overflowing degree of the first one
+        let overflowing = format!(
+            "{{\"version\":1,\"payload\":{{\"num_variables\":3,\"terms\":[{{\"coefficient\":\"{one}\",\"powers\":[[0,{max}],[1,1]]}},{{\"coefficient\":\"{one}\",\"powers\":[[2,1]]}}]}}}}",
+            one = encode_field(&Fq::from(1)),
+            max = usize::MAX
+        );
+        assert!(matches!(
+            SparsePolynomial::<Fq, SparseTerm>::from_json(&overflowing),
+            Err(SumcheckError::MalformedEncoding(_))
+        ));
+
+        let json = sample_polynomial().to_json();
+        let too_many_variables = json.replacen("\"num_variables\":3", "\"num_variables\":70", 1);
+        assert!(matches!(
+            SparsePolynomial::<Fq, SparseTerm>::from_json(&too_many_variables),
+            Err(SumcheckError::MalformedEncoding(_))
+        ));
+    }
+}
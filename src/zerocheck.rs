@@ -0,0 +1,485 @@
+use crate::challenge::{ChallengeSource, OsChallenges};
+use crate::error::SumcheckError;
+use crate::mle::eq_table;
+use crate::polynomial::{univariate_from_evaluations, variable_degrees};
+use crate::proof::SumcheckProof;
+use crate::prover::check_num_variables;
+use crate::transcript::Transcript;
+use crate::verifier::{Subclaim, Verifier};
+use crate::virtual_polynomial::{VirtualPolynomial, VirtualPolynomialProver};
+use ark_ff::Field;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm, Term},
+    univariate::SparsePolynomial as UniSparsePolynomial,
+    DenseMVPolynomial, DenseMultilinearExtension, Polynomial,
+};
+use std::collections::HashMap;
+
+/// Label binding every zero-check transcript to this protocol
+const PROTOCOL_LABEL: &[u8] = b"zerocheck";
+
+/// eq(r, x) = prod_i (r_i * x_i + (1 - r_i) * (1 - x_i)). On the boolean hypercube it is 1 at
+/// x = r and 0 elsewhere, so sum_x f(x) * eq(r, x) is the multilinear extension of f at r.
+pub fn eq_evaluation<F: Field>(r: &[F], x: &[F]) -> F {
+    r.iter()
+        .zip(x)
+        .map(|(r_i, x_i)| *r_i * x_i + (F::one() - r_i) * (F::one() - x_i))
+        .product()
+}
+
+/// Expands f(x) * eq(r, x) into a sparse polynomial, one variable at a time.
+/// Every variable contributes the factor (2 * r_i - 1) * x_i + (1 - r_i), so the result
+/// has up to 2^v times as many terms as f; `multiply_by_eq_virtual` avoids the expansion.
+pub fn multiply_by_eq<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+    r: &[F],
+) -> Result<SparsePolynomial<F, SparseTerm>, SumcheckError<F>> {
+    if r.len() != polynomial.num_vars {
+        return Err(SumcheckError::InvalidParameters(format!(
+            "eq point has {} coordinates, expected {}",
+            r.len(),
+            polynomial.num_vars
+        )));
+    }
+
+    let mut product = polynomial.clone();
+    for (i, r_i) in r.iter().enumerate() {
+        let constant = F::one() - r_i;
+        let linear = r_i.double() - F::one();
+
+        let mut terms = Vec::with_capacity(2 * product.terms.len());
+        for (coeff, term) in &product.terms {
+            let mut powers: Vec<(usize, usize)> = term.iter().copied().collect();
+            powers.push((i, 1));
+            terms.push((*coeff * constant, term.clone()));
+            terms.push((*coeff * linear, SparseTerm::new(powers)));
+        }
+        product = SparsePolynomial::from_coefficients_vec(polynomial.num_vars, terms);
+    }
+    Ok(product)
+}
+
+/// f(x) * eq(r, x) as a `VirtualPolynomial`, with eq(r, x) kept as a single factor given
+/// by `eq_table`. A term c * prod_j x_j^(p_j) of f becomes the product of eq with p_j copies
+/// of the multilinear factor x_j, so the result has as many products as f has terms.
+pub fn multiply_by_eq_virtual<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+    r: &[F],
+) -> Result<VirtualPolynomial<F>, SumcheckError<F>> {
+    let num_variables = polynomial.num_vars;
+    if r.len() != num_variables {
+        return Err(SumcheckError::InvalidParameters(format!(
+            "eq point has {} coordinates, expected {}",
+            r.len(),
+            num_variables
+        )));
+    }
+    check_num_variables(num_variables)?;
+
+    let mut product = VirtualPolynomial::new(num_variables);
+    let eq = product.add_mle(DenseMultilinearExtension::from_evaluations_vec(
+        num_variables,
+        eq_table(r),
+    ))?;
+
+    // The factor x_j of every variable that appears in f, registered once
+    let mut variables = HashMap::new();
+    for (coefficient, term) in &polynomial.terms {
+        let mut factors = vec![eq];
+        for &(variable, power) in term.iter() {
+            let factor = match variables.get(&variable) {
+                Some(&factor) => factor,
+                None => {
+                    let evaluations = (0..1usize << num_variables)
+                        .map(|x| F::from(((x >> variable) & 1) as u64))
+                        .collect();
+                    let factor = product.add_mle(
+                        DenseMultilinearExtension::from_evaluations_vec(num_variables, evaluations),
+                    )?;
+                    variables.insert(variable, factor);
+                    factor
+                }
+            };
+            factors.extend(std::iter::repeat_n(factor, power));
+        }
+        product.add_product(*coefficient, factors)?;
+    }
+    Ok(product)
+}
+
+/// Prover for the claim that a polynomial vanishes on the whole boolean hypercube.
+/// It runs the sumcheck on f(x) * eq(r, x), whose sum is 0 for every r if and only if f
+/// vanishes on the hypercube. eq(r, x) stays a separate factor, so every round costs as
+/// much as a round of the sumcheck on f, instead of expanding the product into up to
+/// 2^v times as many terms.
+pub struct ZeroCheckProver<F: Field> {
+    pub prover: VirtualPolynomialProver<F>,
+}
+
+impl<F: Field> ZeroCheckProver<F> {
+    /// Create a new Prover for `polynomial` and the point `r` chosen by the Verifier
+    pub fn new(
+        polynomial: &SparsePolynomial<F, SparseTerm>,
+        r: &[F],
+    ) -> Result<Self, SumcheckError<F>> {
+        Ok(ZeroCheckProver {
+            prover: VirtualPolynomialProver::new(&multiply_by_eq_virtual(polynomial, r)?),
+        })
+    }
+
+    /// Reduces f(x) * eq(r, x) to a univariate polynomial in `target_var`, interpolated
+    /// from its evaluations. Rounds must be run in order, as for `VirtualPolynomialProver`.
+    pub fn reduce_to_univariate(
+        &mut self,
+        target_var: usize,
+        randoms: &[F],
+    ) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+        let evaluations = self.prover.reduce_to_evaluations(target_var, randoms)?;
+        Ok(univariate_from_evaluations(&evaluations))
+    }
+
+    /// Non-interactive round: reduces the product for `target_var`, absorbs the result
+    /// into the transcript and appends the derived challenge to `randoms`
+    pub fn reduce_with_transcript(
+        &mut self,
+        target_var: usize,
+        randoms: &mut Vec<F>,
+        transcript: &mut Transcript,
+    ) -> Result<UniSparsePolynomial<F>, SumcheckError<F>> {
+        let polynomial = self.reduce_to_univariate(target_var, randoms)?;
+        transcript.append_round_polynomial(&polynomial);
+        randoms.push(transcript.challenge(b"round_challenge"));
+        Ok(polynomial)
+    }
+}
+
+/// Verifier for a zero-check: a sumcheck Verifier with claimed sum 0, whose degree bounds
+/// are one above those of f to account for the eq(r, x) factor
+pub struct ZeroCheckVerifier<F: Field, C: ChallengeSource<F> = OsChallenges> {
+    pub verifier: Verifier<F, C>,
+    pub r: Vec<F>, // Point of the eq(r, x) factor, sent to the Prover before the first round
+}
+
+impl<F: Field> ZeroCheckVerifier<F> {
+    /// Initializes the Verifier with the degree bound of every variable of f
+    /// and a random point r
+    pub fn new(degree_bounds: Vec<usize>) -> Self {
+        Self::new_with_challenge_source(degree_bounds, OsChallenges)
+            .expect("fresh randomness never runs out")
+    }
+
+    /// Initializes the Verifier with degree bounds taken from f and a random point r
+    pub fn from_polynomial(polynomial: &SparsePolynomial<F, SparseTerm>) -> Self {
+        Self::new(variable_degrees(polynomial))
+    }
+
+    /// Initializes the Verifier with a given point r, e.g. one derived from a transcript
+    pub fn with_point(degree_bounds: Vec<usize>, r: Vec<F>) -> Self {
+        let degree_bounds = degree_bounds.into_iter().map(|bound| bound + 1).collect();
+        ZeroCheckVerifier {
+            verifier: Verifier::new(F::zero(), degree_bounds),
+            r,
+        }
+    }
+}
+
+impl<F: Field, C: ChallengeSource<F>> ZeroCheckVerifier<F, C> {
+    /// Initializes the Verifier with the degree bound of every variable of f, drawing the
+    /// point r and then every round challenge from `challenge_source`, e.g.
+    /// `SeededChallenges` so that a run can be replayed
+    pub fn new_with_challenge_source(
+        degree_bounds: Vec<usize>,
+        mut challenge_source: C,
+    ) -> Result<Self, SumcheckError<F>> {
+        let r = (0..degree_bounds.len())
+            .map(|_| challenge_source.next_challenge())
+            .collect::<Result<_, _>>()?;
+        Ok(ZeroCheckVerifier::with_point(degree_bounds, r).with_challenge_source(challenge_source))
+    }
+
+    /// Takes the round challenges from `challenge_source`, see `Verifier::with_challenge_source`
+    pub fn with_challenge_source<D: ChallengeSource<F>>(
+        self,
+        challenge_source: D,
+    ) -> ZeroCheckVerifier<F, D> {
+        ZeroCheckVerifier {
+            verifier: self.verifier.with_challenge_source(challenge_source),
+            r: self.r,
+        }
+    }
+
+    /// Verifies the round polynomial of `variable_index` and returns the next claim
+    pub fn verify_and_challenge(
+        &mut self,
+        poly: &UniSparsePolynomial<F>,
+        variable_index: usize,
+    ) -> Result<F, SumcheckError<F>> {
+        let claim = self.verifier.current_claim;
+        self.verifier
+            .verify_and_challenge(poly, variable_index, &claim)
+    }
+
+    /// Ends the protocol and returns the subclaim on f itself: f evaluated at the challenge
+    /// point must equal the final claim divided by eq(r, point)
+    pub fn finalize(&self) -> Result<Subclaim<F>, SumcheckError<F>> {
+        to_polynomial_subclaim(self.verifier.finalize()?, &self.r)
+    }
+}
+
+/// Turns the subclaim on f(x) * eq(r, x) into a subclaim on f
+fn to_polynomial_subclaim<F: Field>(
+    subclaim: Subclaim<F>,
+    r: &[F],
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    let eq = eq_evaluation(r, &subclaim.point);
+    // eq(r, point) vanishes with probability at most v / |F|; nothing can be said about f then
+    let eq_inverse = eq.inverse().ok_or_else(|| {
+        SumcheckError::InvalidParameters("eq(r, x) vanishes at the challenge point".to_string())
+    })?;
+    Ok(Subclaim {
+        point: subclaim.point,
+        expected_value: subclaim.expected_value * eq_inverse,
+    })
+}
+
+/// Starts the transcript shared by `prove_zero` and `verify_zero` and derives r from it.
+/// f is absorbed before r is drawn: otherwise a Prover could pick f after r, e.g.
+/// f = x0 - r0, which is non-zero on the hypercube but vanishes against eq(r, x).
+fn new_transcript<F: Field>(polynomial: &SparsePolynomial<F, SparseTerm>) -> (Transcript, Vec<F>) {
+    let num_variables = polynomial.num_vars;
+    let mut transcript = Transcript::new(PROTOCOL_LABEL);
+    transcript.append_polynomial_info(num_variables, polynomial.degree());
+    transcript.append_polynomial(polynomial);
+    let r = (0..num_variables)
+        .map(|_| transcript.challenge(b"eq_point"))
+        .collect();
+    transcript.append_claimed_sum(&F::zero());
+    (transcript, r)
+}
+
+/// Non-interactive zero-check: proves that `polynomial` vanishes on the boolean hypercube.
+/// Returns the sumcheck proof over f(x) * eq(r, x) together with the challenge point.
+/// The proof must be verified against the same polynomial.
+pub fn prove_zero<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+) -> Result<(SumcheckProof<F>, Vec<F>), SumcheckError<F>> {
+    let num_variables = polynomial.num_vars;
+    let (mut transcript, r) = new_transcript(polynomial);
+
+    let product = multiply_by_eq_virtual(polynomial, &r)?;
+    let claimed_sum = product.sum_over_all_inputs();
+    let mut prover = ZeroCheckProver {
+        prover: VirtualPolynomialProver::new(&product),
+    };
+
+    let mut challenges = Vec::with_capacity(num_variables);
+    let mut round_polynomials = Vec::with_capacity(num_variables);
+    for i in 0..num_variables {
+        round_polynomials.push(prover.reduce_with_transcript(
+            i,
+            &mut challenges,
+            &mut transcript,
+        )?);
+    }
+
+    Ok((
+        SumcheckProof {
+            claimed_sum,
+            round_polynomials,
+        },
+        challenges,
+    ))
+}
+
+/// Checks a zero-check proof that `polynomial` vanishes on the boolean hypercube and returns
+/// the subclaim on it: f evaluated at the challenge point must equal `expected_value`
+pub fn verify_zero<F: Field>(
+    proof: &SumcheckProof<F>,
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+) -> Result<Subclaim<F>, SumcheckError<F>> {
+    let (num_variables, max_degree) = (polynomial.num_vars, polynomial.degree());
+    if proof.round_polynomials.len() != num_variables {
+        return Err(SumcheckError::WrongRoundCount {
+            expected: num_variables,
+            got: proof.round_polynomials.len(),
+        });
+    }
+    // A vanishing polynomial sums to 0 against eq(r, x), whatever the proof claims
+    if !proof.claimed_sum.is_zero() {
+        return Err(SumcheckError::SumMismatch {
+            round: 0,
+            expected: F::zero(),
+            got: proof.claimed_sum,
+        });
+    }
+
+    let (transcript, r) = new_transcript(polynomial);
+    let mut verifier = ZeroCheckVerifier::with_point(vec![max_degree; num_variables], r)
+        .with_challenge_source(transcript);
+
+    for (round, polynomial) in proof.round_polynomials.iter().enumerate() {
+        verifier.verify_and_challenge(polynomial, round)?;
+    }
+
+    verifier.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::{ScriptedChallenges, SeededChallenges};
+    use crate::parser::parse_polynomial;
+    use crate::prover::Prover;
+    use ark_test_curves::fp128::Fq;
+
+    #[test]
+    fn test_zero_check_accepts_vanishing_polynomial() {
+        // x^2 - x vanishes on {0, 1}
+        let poly = parse_polynomial::<Fq>("x0^2*x2 - x0*x2 + 3*x1^3 - 3*x1").unwrap();
+
+        let mut verifier = ZeroCheckVerifier::from_polynomial(&poly);
+        let mut prover = ZeroCheckProver::new(&poly, &verifier.r).unwrap();
+        for i in 0..poly.num_vars {
+            let round_polynomial = prover
+                .reduce_to_univariate(i, &verifier.verifier.challenge_values)
+                .unwrap();
+            verifier.verify_and_challenge(&round_polynomial, i).unwrap();
+        }
+        let subclaim = verifier.finalize().unwrap();
+        assert_eq!(subclaim.check(&poly), Ok(()));
+
+        let (proof, challenges) = prove_zero(&poly).unwrap();
+        let subclaim = verify_zero(&proof, &poly).unwrap();
+        assert_eq!(subclaim.point, challenges);
+        assert_eq!(subclaim.check(&poly), Ok(()));
+    }
+
+    #[test]
+    fn test_zero_check_rejects_polynomial_with_zero_sum() {
+        // x0 - x1 sums to 0 over the hypercube but does not vanish on it
+        let poly = parse_polynomial::<Fq>("x0 - x1").unwrap();
+
+        let (proof, _) = prove_zero(&poly).unwrap();
+        assert!(matches!(
+            verify_zero(&proof, &poly),
+            Err(SumcheckError::SumMismatch { round: 0, .. })
+        ));
+
+        // Claiming 0 anyway is caught by the first round check
+        let mut forged = proof;
+        forged.claimed_sum = Fq::from(0);
+        assert!(matches!(
+            verify_zero(&forged, &poly),
+            Err(SumcheckError::SumMismatch { round: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_zero_check_rejects_polynomial_chosen_after_r() {
+        // r as derived when only the shape of f was absorbed
+        let mut transcript = Transcript::new(PROTOCOL_LABEL);
+        transcript.append_polynomial_info(1, 1);
+        let r0: Fq = transcript.challenge(b"eq_point");
+        transcript.append_claimed_sum(&Fq::from(0));
+
+        // f = x0 - r0 is non-zero on {0, 1}, yet f(x) * eq(r0, x) sums to f(r0) = 0
+        let poly = SparsePolynomial::from_coefficients_vec(
+            1,
+            vec![
+                (Fq::from(1), SparseTerm::new(vec![(0, 1)])),
+                (-r0, SparseTerm::new(vec![])),
+            ],
+        );
+        let mut prover = ZeroCheckProver::new(&poly, &[r0]).unwrap();
+        let mut challenges = Vec::new();
+        let round_polynomial = prover
+            .reduce_with_transcript(0, &mut challenges, &mut transcript)
+            .unwrap();
+        let forged = SumcheckProof {
+            claimed_sum: Fq::from(0),
+            round_polynomials: vec![round_polynomial],
+        };
+
+        // Against that r, the forgery passes every check
+        let mut transcript = Transcript::new(PROTOCOL_LABEL);
+        transcript.append_polynomial_info(1, 1);
+        let _: Fq = transcript.challenge(b"eq_point");
+        transcript.append_claimed_sum(&Fq::from(0));
+        let mut verifier =
+            ZeroCheckVerifier::with_point(vec![1], vec![r0]).with_challenge_source(transcript);
+        verifier
+            .verify_and_challenge(&forged.round_polynomials[0], 0)
+            .unwrap();
+        assert_eq!(verifier.finalize().unwrap().check(&poly), Ok(()));
+
+        // With f absorbed before r, it is rejected
+        let result = verify_zero(&forged, &poly).and_then(|subclaim| subclaim.check(&poly));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_prover_keeps_eq_separate_with_the_same_rounds() {
+        let poly = parse_polynomial::<Fq>("3*x0^2*x2 - x0*x1 + 5*x1^3*x2 + 2").unwrap();
+        let r = vec![Fq::from(3), Fq::from(8), Fq::from(5)];
+        let mut expanded = Prover::new_with_polynomial(multiply_by_eq(&poly, &r).unwrap());
+        let mut prover = ZeroCheckProver::new(&poly, &r).unwrap();
+
+        let mut randoms = Vec::new();
+        for i in 0..poly.num_vars {
+            assert_eq!(
+                prover.reduce_to_univariate(i, &randoms).unwrap(),
+                expanded.reduce_to_univariate(i, &randoms).unwrap()
+            );
+            randoms.push(Fq::from(11 + i as u64));
+        }
+    }
+
+    #[test]
+    fn test_seeded_zero_check_replays() {
+        let poly = parse_polynomial::<Fq>("x0^2*x2 - x0*x2 + 3*x1^3 - 3*x1").unwrap();
+        let run = |seed| {
+            let mut verifier = ZeroCheckVerifier::new_with_challenge_source(
+                variable_degrees(&poly),
+                SeededChallenges::new(seed),
+            )
+            .unwrap();
+            let mut prover = ZeroCheckProver::new(&poly, &verifier.r).unwrap();
+            for i in 0..poly.num_vars {
+                let round_polynomial = prover
+                    .reduce_to_univariate(i, &verifier.verifier.challenge_values)
+                    .unwrap();
+                verifier.verify_and_challenge(&round_polynomial, i).unwrap();
+            }
+            (verifier.r.clone(), verifier.finalize().unwrap())
+        };
+
+        let (r, subclaim) = run(7);
+        assert_eq!(run(7), (r.clone(), subclaim.clone()));
+        assert_ne!(run(8).0, r);
+        assert_eq!(subclaim.check(&poly), Ok(()));
+
+        // The point r is drawn from the source too, so an empty script fails up front
+        assert!(ZeroCheckVerifier::new_with_challenge_source(
+            variable_degrees(&poly),
+            ScriptedChallenges::<Fq>::new(vec![]),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_multiply_by_eq_selects_point_on_hypercube() {
+        let poly = parse_polynomial::<Fq>("5*x0*x1 + 2*x1 + 1").unwrap();
+        let r = vec![Fq::from(1), Fq::from(0)];
+        let product = multiply_by_eq(&poly, &r).unwrap();
+        for i in 0..4u64 {
+            let x = vec![Fq::from(i & 1), Fq::from(i >> 1)];
+            let expected = if x == r {
+                poly.evaluate(&x)
+            } else {
+                Fq::from(0)
+            };
+            assert_eq!(product.evaluate(&x), expected);
+        }
+        assert!(multiply_by_eq(&poly, &r[..1]).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+//! Runs the `sumcheck` binary end to end and checks its exit status in every outcome:
+//! 0 when accepted, 1 when a proof is rejected and 2 on a usage, I/O or decoding error
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// The field element 1, as the hex string of its compressed bytes
+const ONE: &str = "01000000000000000000000000000000";
+
+fn sumcheck(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sumcheck"))
+        .args(args)
+        .output()
+        .expect("the binary runs")
+}
+
+/// A scratch directory of its own for every test, as the tests run in parallel
+fn scratch_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sumcheck-cli-{}-{}", std::process::id(), test));
+    fs::create_dir_all(&dir).expect("the scratch directory can be created");
+    dir
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().expect("temporary paths are UTF-8")
+}
+
+/// Asserts the exit status, and that the tool failed cleanly rather than by panicking
+fn assert_status(output: &Output, expected: i32) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(expected), "stderr: {}", stderr);
+    assert!(!stderr.contains("panicked"), "stderr: {}", stderr);
+}
+
+/// Generates the polynomial of `seed` and a proof of its sum into `dir`
+fn generate_and_prove(dir: &Path, seed: &str) -> (PathBuf, PathBuf) {
+    let polynomial = dir.join(format!("polynomial-{}.json", seed));
+    let proof = dir.join(format!("proof-{}.json", seed));
+    assert_status(
+        &sumcheck(&[
+            "generate",
+            "--seed",
+            seed,
+            "--output",
+            path_str(&polynomial),
+        ]),
+        0,
+    );
+    assert_status(
+        &sumcheck(&[
+            "prove",
+            "--polynomial",
+            path_str(&polynomial),
+            "--output",
+            path_str(&proof),
+        ]),
+        0,
+    );
+    (polynomial, proof)
+}
+
+/// A polynomial message with the given number of variables and term powers
+fn polynomial_json(num_variables: usize, terms: &[&str]) -> String {
+    let terms: Vec<String> = terms
+        .iter()
+        .map(|powers| format!("{{\"coefficient\":\"{}\",\"powers\":{}}}", ONE, powers))
+        .collect();
+    format!(
+        "{{\"version\":1,\"payload\":{{\"num_variables\":{},\"terms\":[{}]}}}}",
+        num_variables,
+        terms.join(",")
+    )
+}
+
+#[test]
+fn test_generate_prove_and_verify() {
+    let dir = scratch_dir("accept");
+    let (polynomial, proof) = generate_and_prove(&dir, "42");
+
+    let output = sumcheck(&[
+        "verify",
+        "--proof",
+        path_str(&proof),
+        "--polynomial",
+        path_str(&polynomial),
+    ]);
+    assert_status(&output, 0);
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("accepted"));
+
+    // The hex format is read back the same way
+    let hex_proof = dir.join("proof.hex");
+    assert_status(
+        &sumcheck(&[
+            "prove",
+            "--polynomial",
+            path_str(&polynomial),
+            "--format",
+            "hex",
+            "--output",
+            path_str(&hex_proof),
+        ]),
+        0,
+    );
+    assert_status(
+        &sumcheck(&[
+            "verify",
+            "--proof",
+            path_str(&hex_proof),
+            "--polynomial",
+            path_str(&polynomial),
+        ]),
+        0,
+    );
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_proof_of_another_polynomial_is_rejected() {
+    let dir = scratch_dir("reject");
+    let (polynomial, _) = generate_and_prove(&dir, "1");
+    let (_, other_proof) = generate_and_prove(&dir, "2");
+
+    let output = sumcheck(&[
+        "verify",
+        "--proof",
+        path_str(&other_proof),
+        "--polynomial",
+        path_str(&polynomial),
+    ]);
+    assert_status(&output, 1);
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("rejected"));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_usage_errors() {
+    assert_status(&sumcheck(&[]), 2);
+    assert_status(&sumcheck(&["frobnicate"]), 2);
+    assert_status(&sumcheck(&["generate"]), 2);
+    assert_status(&sumcheck(&["generate", "--seed", "many"]), 2);
+    assert_status(&sumcheck(&["verify", "--proof"]), 2);
+    assert_status(
+        &sumcheck(&["prove", "--polynomial", "p.json", "--bogus", "1"]),
+        2,
+    );
+    assert_status(&sumcheck(&["help"]), 0);
+}
+
+#[test]
+fn test_unreadable_and_malformed_files() {
+    let dir = scratch_dir("malformed");
+    let (polynomial, proof) = generate_and_prove(&dir, "7");
+
+    let missing = dir.join("missing.json");
+    assert_status(&sumcheck(&["prove", "--polynomial", path_str(&missing)]), 2);
+
+    let garbage = dir.join("garbage.json");
+    fs::write(&garbage, "{\"version\":1,\"payload\":[]}").unwrap();
+    assert_status(
+        &sumcheck(&[
+            "verify",
+            "--proof",
+            path_str(&garbage),
+            "--polynomial",
+            path_str(&polynomial),
+        ]),
+        2,
+    );
+
+    // Summing the powers of the first term overflows
+    let overflowing = dir.join("overflowing.json");
+    fs::write(
+        &overflowing,
+        polynomial_json(3, &[&format!("[[0,{}],[1,1]]", usize::MAX), "[[2,1]]"]),
+    )
+    .unwrap();
+    assert_status(
+        &sumcheck(&[
+            "verify",
+            "--proof",
+            path_str(&proof),
+            "--polynomial",
+            path_str(&overflowing),
+        ]),
+        2,
+    );
+
+    // Too many variables to sum over the hypercube
+    let too_many_variables = dir.join("too-many-variables.json");
+    fs::write(&too_many_variables, polynomial_json(70, &["[[69,1]]"])).unwrap();
+    assert_status(
+        &sumcheck(&["prove", "--polynomial", path_str(&too_many_variables)]),
+        2,
+    );
+
+    // A hand-written polynomial within the limits is proved
+    let valid = dir.join("valid.json");
+    fs::write(&valid, polynomial_json(3, &["[[0,2],[1,1]]", "[[2,1]]"])).unwrap();
+    assert_status(&sumcheck(&["prove", "--polynomial", path_str(&valid)]), 0);
+
+    fs::remove_dir_all(&dir).ok();
+}